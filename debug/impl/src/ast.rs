@@ -0,0 +1,100 @@
+use crate::attr;
+use syn::{Data, DeriveInput, Error, Fields, Ident, Member, Result, Type};
+
+pub struct Input<'a> {
+    pub ident: &'a Ident,
+    pub attrs: attr::Container,
+    pub data: Body<'a>,
+}
+
+pub enum Body<'a> {
+    Struct(Style, Vec<Field<'a>>),
+    Enum(Vec<Variant<'a>>),
+}
+
+pub struct Variant<'a> {
+    pub ident: &'a Ident,
+    pub attrs: attr::Variant,
+    pub style: Style,
+    pub fields: Vec<Field<'a>>,
+}
+
+pub struct Field<'a> {
+    pub member: Member,
+    pub attrs: attr::Field,
+    pub ty: &'a Type,
+}
+
+#[derive(Copy, Clone)]
+pub enum Style {
+    Named,
+    Tuple,
+    Unit,
+}
+
+impl<'a> Input<'a> {
+    pub fn from_syn(input: &'a DeriveInput) -> Result<Self> {
+        let attrs = attr::container(&input.attrs)?;
+        let data = match &input.data {
+            Data::Struct(data) => {
+                let (style, fields) = fields_from_syn(&data.fields)?;
+                Body::Struct(style, fields)
+            }
+            Data::Enum(data) => {
+                let variants = data
+                    .variants
+                    .iter()
+                    .map(|variant| {
+                        let (style, fields) = fields_from_syn(&variant.fields)?;
+                        Ok(Variant {
+                            ident: &variant.ident,
+                            attrs: attr::variant(&variant.attrs)?,
+                            style,
+                            fields,
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+                Body::Enum(variants)
+            }
+            Data::Union(data) => {
+                let msg = "CustomDebug does not support unions";
+                return Err(Error::new(data.union_token.span, msg));
+            }
+        };
+        Ok(Input {
+            ident: &input.ident,
+            attrs,
+            data,
+        })
+    }
+
+    pub fn fields(&self) -> Box<dyn Iterator<Item = &Field<'a>> + '_> {
+        match &self.data {
+            Body::Struct(_, fields) => Box::new(fields.iter()),
+            Body::Enum(variants) => Box::new(variants.iter().flat_map(|v| &v.fields)),
+        }
+    }
+}
+
+fn fields_from_syn(fields: &Fields) -> Result<(Style, Vec<Field<'_>>)> {
+    let style = match fields {
+        Fields::Named(_) => Style::Named,
+        Fields::Unnamed(_) => Style::Tuple,
+        Fields::Unit => Style::Unit,
+    };
+    let fields = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            Ok(Field {
+                member: match &field.ident {
+                    Some(ident) => Member::Named(ident.clone()),
+                    None => Member::Unnamed(i.into()),
+                },
+                attrs: attr::field(&field.attrs)?,
+                ty: &field.ty,
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok((style, fields))
+}
@@ -0,0 +1,411 @@
+use crate::fmt::Format;
+use syn::punctuated::Punctuated;
+use syn::{
+    Attribute, Error, Expr, ExprLit, Ident, Lit, LitInt, LitStr, Meta, Path, Result, Token,
+    WherePredicate,
+};
+
+pub struct Container {
+    // Replaces all inferred bounds, from #[debug(bound = "...")].
+    pub bound: Option<Vec<WherePredicate>>,
+    // Replaces the whole output, from #[debug("...")].
+    pub format: Option<Format>,
+    // Replaces the whole output under {:#?}, from #[debug(alt = "...")].
+    pub alt: Option<Format>,
+    // Types whose Debug impl does not need their type parameters to implement
+    // Debug, from #[debug(transparent_wrappers = "...")].
+    pub transparent_wrappers: Vec<Ident>,
+    // Hide fields that are equal to their default value, from
+    // #[debug(skip_defaults)].
+    pub skip_defaults: bool,
+    // Print `..` instead of the fields once this many impls with the
+    // attribute are nested, from #[debug(max_depth = N)].
+    pub max_depth: Option<usize>,
+    // Generate a function that prints the given type using this type's
+    // fields and attributes, from #[debug(remote = "path")].
+    pub remote: Option<Path>,
+    // Always end the output with `..`, from #[debug(non_exhaustive)].
+    pub non_exhaustive: Option<Path>,
+}
+
+pub struct Variant {
+    // From #[debug(non_exhaustive)] on the variant.
+    pub non_exhaustive: bool,
+}
+
+pub struct Field {
+    // From #[debug = "..."].
+    pub format: Option<Format>,
+    // Used instead of `format` under {:#?}, from #[debug(alt = "...")].
+    pub alt: Option<Format>,
+    // From #[debug(with = "path")].
+    pub with: Option<Path>,
+    // Replaces the bounds inferred from this field, from
+    // #[debug(bound = "...")].
+    pub bound: Option<Vec<WherePredicate>>,
+    // Maximum number of elements to print, from #[debug(limit = N)].
+    pub limit: Option<usize>,
+    // From #[debug(hex)] or #[debug(ascii)].
+    pub bytes: Option<Bytes>,
+    // Leave the field out of the output, from #[debug(skip)].
+    pub skip: bool,
+    // Print a placeholder instead of the value, from #[debug(redact)].
+    pub redact: Option<Path>,
+    // Leave the field out of the output when the function returns true, from
+    // #[debug(skip_if = "path")].
+    pub skip_if: Option<Path>,
+    // Print `<cycle>` for an Rc or Arc that is already being printed, from
+    // #[debug(cycle_safe)].
+    pub cycle_safe: Option<Path>,
+}
+
+#[derive(Copy, Clone)]
+pub enum Bytes {
+    Hex,
+    Ascii,
+}
+
+pub struct Display {
+    // From #[display("...")].
+    pub format: Option<Format>,
+    // From #[display(transparent)].
+    pub transparent: Option<Path>,
+    // Replaces all inferred bounds, from #[display(bound = "...")].
+    pub bound: Option<Vec<WherePredicate>>,
+}
+
+pub fn container(attrs: &[Attribute]) -> Result<Container> {
+    let mut container = Container {
+        bound: None,
+        format: None,
+        alt: None,
+        transparent_wrappers: Vec::new(),
+        skip_defaults: false,
+        max_depth: None,
+        remote: None,
+        non_exhaustive: None,
+    };
+
+    for attr in attrs {
+        if !attr.path().is_ident("debug") {
+            continue;
+        }
+        let list = match &attr.meta {
+            Meta::List(list) => list,
+            _ => {
+                let msg = "expected #[debug(...)] on the container";
+                return Err(Error::new_spanned(attr, msg));
+            }
+        };
+        if let Ok(lit) = syn::parse2::<LitStr>(list.tokens.clone()) {
+            if container.format.is_some() {
+                return Err(Error::new_spanned(attr, "duplicate debug format string"));
+            }
+            container.format = Some(container_format(Format::parse(&lit)?)?);
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("bound") {
+                let lit: LitStr = meta.value()?.parse()?;
+                if container.bound.is_some() {
+                    return Err(meta.error("duplicate debug attribute `bound`"));
+                }
+                container.bound = Some(parse_bound(&lit)?);
+                Ok(())
+            } else if meta.path.is_ident("alt") {
+                let lit: LitStr = meta.value()?.parse()?;
+                if container.alt.is_some() {
+                    return Err(meta.error("duplicate debug attribute `alt`"));
+                }
+                container.alt = Some(container_format(Format::parse(&lit)?)?);
+                Ok(())
+            } else if meta.path.is_ident("transparent_wrappers") {
+                let lit: LitStr = meta.value()?.parse()?;
+                let wrappers = lit.parse_with(Punctuated::<Ident, Token![,]>::parse_terminated)?;
+                container.transparent_wrappers.extend(wrappers);
+                Ok(())
+            } else if meta.path.is_ident("skip_defaults") {
+                if container.skip_defaults {
+                    return Err(meta.error("duplicate debug attribute `skip_defaults`"));
+                }
+                container.skip_defaults = true;
+                Ok(())
+            } else if meta.path.is_ident("max_depth") {
+                let lit: LitInt = meta.value()?.parse()?;
+                if container.max_depth.is_some() {
+                    return Err(meta.error("duplicate debug attribute `max_depth`"));
+                }
+                let max_depth = lit.base10_parse()?;
+                if max_depth == 0 {
+                    return Err(Error::new(lit.span(), "`max_depth` must be at least 1"));
+                }
+                container.max_depth = Some(max_depth);
+                Ok(())
+            } else if meta.path.is_ident("remote") {
+                let lit: LitStr = meta.value()?.parse()?;
+                if container.remote.is_some() {
+                    return Err(meta.error("duplicate debug attribute `remote`"));
+                }
+                container.remote = Some(lit.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("non_exhaustive") {
+                if container.non_exhaustive.is_some() {
+                    return Err(meta.error("duplicate debug attribute `non_exhaustive`"));
+                }
+                container.non_exhaustive = Some(meta.path);
+                Ok(())
+            } else {
+                let msg = "unsupported debug attribute, expected `bound`, `alt`, `transparent_wrappers`, `skip_defaults`, `max_depth`, `remote` or `non_exhaustive`";
+                Err(meta.error(msg))
+            }
+        })?;
+    }
+
+    Ok(container)
+}
+
+pub fn variant(attrs: &[Attribute]) -> Result<Variant> {
+    let mut variant = Variant {
+        non_exhaustive: false,
+    };
+
+    for attr in attrs {
+        if !attr.path().is_ident("debug") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("non_exhaustive") {
+                if variant.non_exhaustive {
+                    return Err(meta.error("duplicate debug attribute `non_exhaustive`"));
+                }
+                variant.non_exhaustive = true;
+                Ok(())
+            } else if meta.path.is_ident("bound") {
+                let msg =
+                    "#[debug(bound = \"...\")] goes on the container or a field, not a variant";
+                Err(meta.error(msg))
+            } else {
+                Err(meta.error("unsupported debug attribute, expected `non_exhaustive`"))
+            }
+        })?;
+    }
+
+    Ok(variant)
+}
+
+pub fn field(attrs: &[Attribute]) -> Result<Field> {
+    let mut field = Field {
+        format: None,
+        alt: None,
+        with: None,
+        bound: None,
+        limit: None,
+        bytes: None,
+        skip: false,
+        redact: None,
+        skip_if: None,
+        cycle_safe: None,
+    };
+
+    for attr in attrs {
+        if !attr.path().is_ident("debug") {
+            continue;
+        }
+        if let Meta::NameValue(nv) = &attr.meta {
+            let format = match &nv.value {
+                Expr::Lit(ExprLit {
+                    lit: Lit::Str(lit), ..
+                }) => lit,
+                value => {
+                    let msg = "expected format string: #[debug = \"...\"]";
+                    return Err(Error::new_spanned(value, msg));
+                }
+            };
+            if field.format.is_some() {
+                return Err(Error::new_spanned(attr, "duplicate debug format string"));
+            }
+            field.format = Some(Format::parse(format)?);
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("with") {
+                let lit: LitStr = meta.value()?.parse()?;
+                if field.with.is_some() {
+                    return Err(meta.error("duplicate debug attribute `with`"));
+                }
+                field.with = Some(lit.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("alt") {
+                let lit: LitStr = meta.value()?.parse()?;
+                if field.alt.is_some() {
+                    return Err(meta.error("duplicate debug attribute `alt`"));
+                }
+                field.alt = Some(Format::parse(&lit)?);
+                Ok(())
+            } else if meta.path.is_ident("bound") {
+                let lit: LitStr = meta.value()?.parse()?;
+                if field.bound.is_some() {
+                    return Err(meta.error("duplicate debug attribute `bound`"));
+                }
+                field.bound = Some(parse_bound(&lit)?);
+                Ok(())
+            } else if meta.path.is_ident("limit") {
+                let lit: LitInt = meta.value()?.parse()?;
+                if field.limit.is_some() {
+                    return Err(meta.error("duplicate debug attribute `limit`"));
+                }
+                field.limit = Some(lit.base10_parse()?);
+                Ok(())
+            } else if meta.path.is_ident("hex") || meta.path.is_ident("ascii") {
+                if field.bytes.is_some() {
+                    return Err(meta.error("only one of `hex` or `ascii` is allowed"));
+                }
+                field.bytes = Some(if meta.path.is_ident("hex") {
+                    Bytes::Hex
+                } else {
+                    Bytes::Ascii
+                });
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                if field.skip {
+                    return Err(meta.error("duplicate debug attribute `skip`"));
+                }
+                field.skip = true;
+                Ok(())
+            } else if meta.path.is_ident("redact") {
+                if field.redact.is_some() {
+                    return Err(meta.error("duplicate debug attribute `redact`"));
+                }
+                field.redact = Some(meta.path);
+                Ok(())
+            } else if meta.path.is_ident("skip_if") {
+                let lit: LitStr = meta.value()?.parse()?;
+                if field.skip_if.is_some() {
+                    return Err(meta.error("duplicate debug attribute `skip_if`"));
+                }
+                field.skip_if = Some(lit.parse()?);
+                Ok(())
+            } else if meta.path.is_ident("cycle_safe") {
+                if field.cycle_safe.is_some() {
+                    return Err(meta.error("duplicate debug attribute `cycle_safe`"));
+                }
+                field.cycle_safe = Some(meta.path);
+                Ok(())
+            } else {
+                Err(meta.error(
+                    "unsupported debug attribute, expected `with`, `alt`, `bound`, `limit`, `hex`, `ascii`, `skip`, `redact`, `skip_if` or `cycle_safe`",
+                ))
+            }
+        })?;
+    }
+
+    if let (Some(format), Some(_)) = (&field.format, &field.with) {
+        let msg = "#[debug = \"...\"] cannot be combined with #[debug(with = \"...\")]";
+        return Err(Error::new(format.lit.span(), msg));
+    }
+    if field.limit.is_some() || field.bytes.is_some() {
+        let msg = "`limit`, `hex` and `ascii` cannot be combined with a format string or `with`";
+        if let Some(format) = &field.format {
+            return Err(Error::new(format.lit.span(), msg));
+        }
+        if let Some(with) = &field.with {
+            return Err(Error::new_spanned(with, msg));
+        }
+    }
+
+    if let Some(redact) = &field.redact {
+        let formatted = field.format.is_some()
+            || field.alt.is_some()
+            || field.with.is_some()
+            || field.limit.is_some()
+            || field.bytes.is_some();
+        if formatted {
+            let msg = "`redact` cannot be combined with other formatting attributes";
+            return Err(Error::new_spanned(redact, msg));
+        }
+    }
+    if let Some(cycle_safe) = &field.cycle_safe {
+        let formatted = field.format.is_some()
+            || field.with.is_some()
+            || field.limit.is_some()
+            || field.bytes.is_some()
+            || field.redact.is_some();
+        if formatted {
+            let msg = "`cycle_safe` cannot be combined with a format string, `with`, `limit`, `hex`, `ascii` or `redact`";
+            return Err(Error::new_spanned(cycle_safe, msg));
+        }
+    }
+    if let (true, Some(skip_if)) = (field.skip, &field.skip_if) {
+        let msg = "#[debug(skip)] cannot be combined with #[debug(skip_if = \"...\")]";
+        return Err(Error::new_spanned(skip_if, msg));
+    }
+
+    Ok(field)
+}
+
+pub fn display(attrs: &[Attribute]) -> Result<Display> {
+    let mut display = Display {
+        format: None,
+        transparent: None,
+        bound: None,
+    };
+
+    for attr in attrs {
+        if !attr.path().is_ident("display") {
+            continue;
+        }
+        let list = attr.meta.require_list()?;
+        if let Ok(lit) = syn::parse2::<LitStr>(list.tokens.clone()) {
+            if display.format.is_some() {
+                return Err(Error::new_spanned(attr, "duplicate display format string"));
+            }
+            display.format = Some(container_format(Format::parse_bare(&lit)?)?);
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("transparent") {
+                if display.transparent.is_some() {
+                    return Err(meta.error("duplicate display attribute `transparent`"));
+                }
+                display.transparent = Some(meta.path);
+                Ok(())
+            } else if meta.path.is_ident("bound") {
+                let lit: LitStr = meta.value()?.parse()?;
+                if display.bound.is_some() {
+                    return Err(meta.error("duplicate display attribute `bound`"));
+                }
+                display.bound = Some(parse_bound(&lit)?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported display attribute, expected `transparent` or `bound`"))
+            }
+        })?;
+    }
+
+    if let (Some(format), Some(_)) = (&display.format, &display.transparent) {
+        let msg = "#[display(\"...\")] cannot be combined with #[display(transparent)]";
+        return Err(Error::new(format.lit.span(), msg));
+    }
+
+    Ok(display)
+}
+
+// A format string for the whole container has no field of its own to print,
+// so `{}` has nothing to refer to.
+fn container_format(format: Format) -> Result<Format> {
+    if format.positional {
+        let msg = "positional arguments are not supported here, refer to fields by name";
+        return Err(Error::new(format.lit.span(), msg));
+    }
+    Ok(format)
+}
+
+fn parse_bound(lit: &LitStr) -> Result<Vec<WherePredicate>> {
+    let bound = lit
+        .parse_with(Punctuated::<WherePredicate, Token![,]>::parse_terminated)
+        .map_err(|error| {
+            let msg = format!("invalid bound `{}`: {}", lit.value(), error);
+            Error::new(lit.span(), msg)
+        })?;
+    Ok(bound.into_iter().collect())
+}
@@ -0,0 +1,147 @@
+use proc_macro2::TokenStream;
+use quote::ToTokens;
+use std::collections::BTreeSet as Set;
+use syn::visit::{self, Visit};
+use syn::{
+    parse_quote, Generics, Ident, Type, TypeBareFn, TypeParamBound, TypePath, TypePtr,
+    TypeTraitObject, WherePredicate,
+};
+
+// Infers the where-clause of the generated impl from pairs of a field type and
+// the trait through which that field gets formatted.
+//
+// Every type parameter that is mentioned by some field gets a `T: Trait`
+// bound, unless the only mentions are in positions that are known to format
+// without needing one: inside of a transparent wrapper like PhantomData, a fn
+// pointer, a `dyn Fn` trait object, or a raw pointer. Associated types of a
+// type parameter, like `T::Value` or `<T as Trait>::Value`, get a bound of
+// their own instead of one on `T`, at any depth within the field type.
+//
+// The caller can name additional transparent wrappers, matched by the last
+// segment of their path like PhantomData is.
+pub fn infer<'a>(
+    generics: &Generics,
+    transparent: &[Ident],
+    fields: impl IntoIterator<Item = (&'a Type, TokenStream)>,
+) -> Vec<WherePredicate> {
+    let params = generics
+        .type_params()
+        .map(|param| &param.ident)
+        .collect::<Set<_>>();
+
+    let mut used = Vec::new();
+    let mut associated = Vec::new();
+    for (ty, bound) in fields {
+        let mut visitor = FieldVisitor {
+            params: &params,
+            transparent,
+            used: Set::new(),
+            associated: Vec::new(),
+        };
+        visitor.visit_type(ty);
+        for param in visitor.used {
+            used.push((param, bound.clone()));
+        }
+        for path in visitor.associated {
+            associated.push((path, bound.clone()));
+        }
+    }
+
+    let mut bounds = Vec::new();
+    let mut seen = Set::new();
+    for param in generics.type_params() {
+        for (ident, bound) in &used {
+            if *ident == param.ident && seen.insert((ident.to_string(), bound.to_string())) {
+                bounds.push(parse_quote!(#ident: #bound));
+            }
+        }
+    }
+    for (path, bound) in &associated {
+        let key = (path.to_token_stream().to_string(), bound.to_string());
+        if seen.insert(key) {
+            bounds.push(parse_quote!(#path: #bound));
+        }
+    }
+    bounds
+}
+
+// Implementations of Debug in the standard library for types that are generic
+// over some T, without requiring `T: Debug`.
+const TRANSPARENT_WRAPPERS: &[&str] = &["PhantomData", "NonNull", "Weak", "Discriminant"];
+
+struct FieldVisitor<'a> {
+    params: &'a Set<&'a Ident>,
+    transparent: &'a [Ident],
+    used: Set<Ident>,
+    associated: Vec<TypePath>,
+}
+
+impl<'a> FieldVisitor<'a> {
+    fn is_transparent(&self, ty: &TypePath) -> bool {
+        match ty.path.segments.last() {
+            Some(last) if ty.qself.is_none() => {
+                TRANSPARENT_WRAPPERS
+                    .iter()
+                    .any(|wrapper| last.ident == wrapper)
+                    || self.transparent.contains(&last.ident)
+            }
+            _ => false,
+        }
+    }
+
+    fn mentions_param(&self, ty: &Type) -> bool {
+        let mut visitor = FieldVisitor {
+            params: self.params,
+            transparent: &[],
+            used: Set::new(),
+            associated: Vec::new(),
+        };
+        visitor.visit_type(ty);
+        !visitor.used.is_empty() || !visitor.associated.is_empty()
+    }
+}
+
+impl<'ast, 'a> Visit<'ast> for FieldVisitor<'a> {
+    fn visit_type_path(&mut self, ty: &'ast TypePath) {
+        if self.is_transparent(ty) {
+            return;
+        }
+        match &ty.qself {
+            None => {
+                let first = &ty.path.segments[0].ident;
+                if self.params.contains(first) {
+                    if ty.path.segments.len() == 1 {
+                        self.used.insert(first.clone());
+                    } else {
+                        self.associated.push(ty.clone());
+                        return;
+                    }
+                }
+            }
+            Some(qself) => {
+                if self.mentions_param(&qself.ty) {
+                    self.associated.push(ty.clone());
+                    return;
+                }
+            }
+        }
+        visit::visit_type_path(self, ty);
+    }
+
+    fn visit_type_bare_fn(&mut self, _ty: &'ast TypeBareFn) {}
+
+    fn visit_type_ptr(&mut self, _ty: &'ast TypePtr) {}
+
+    fn visit_type_trait_object(&mut self, ty: &'ast TypeTraitObject) {
+        let is_fn = ty.bounds.iter().any(|bound| match bound {
+            TypeParamBound::Trait(bound) => match bound.path.segments.last() {
+                Some(last) => ["Fn", "FnMut", "FnOnce"].iter().any(|f| last.ident == f),
+                None => false,
+            },
+            _ => false,
+        });
+        if !is_fn {
+            visit::visit_type_trait_object(self, ty);
+        }
+    }
+}
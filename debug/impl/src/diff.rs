@@ -0,0 +1,163 @@
+use crate::ast::{Body, Field, Input};
+use crate::bound;
+use crate::expand::{debug_value, helpers, member_name};
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{DeriveInput, Result};
+
+pub fn derive(node: &DeriveInput) -> Result<TokenStream> {
+    let input = Input::from_syn(node)?;
+
+    let mut generics = node.generics.clone();
+    let bounds = match &input.attrs.bound {
+        Some(bound) => bound.clone(),
+        None => {
+            let inferred = input.fields().filter(|field| field.attrs.bound.is_none());
+            let mut fields = Vec::new();
+            for field in inferred.filter(|field| !field.attrs.skip) {
+                if !is_rendered(field) {
+                    fields.push((field.ty, quote!(::derive_debug::DebugDiff)));
+                } else if field.attrs.with.is_none()
+                    && field.attrs.bytes.is_none()
+                    && field.attrs.redact.is_none()
+                {
+                    fields.push((field.ty, quote!(::core::fmt::Debug)));
+                }
+            }
+            let mut bounds =
+                bound::infer(&node.generics, &input.attrs.transparent_wrappers, fields);
+            for field in input.fields() {
+                if let Some(bound) = &field.attrs.bound {
+                    bounds.extend(bound.iter().cloned());
+                }
+            }
+            bounds
+        }
+    };
+    generics.make_where_clause().predicates.extend(bounds);
+
+    let ident = input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = match &input.data {
+        Body::Struct(_style, fields) => {
+            let this = fields
+                .iter()
+                .map(|field| {
+                    let member = &field.member;
+                    quote!(self.#member)
+                })
+                .collect::<Vec<_>>();
+            let other = fields
+                .iter()
+                .map(|field| {
+                    let member = &field.member;
+                    quote!(other.#member)
+                })
+                .collect::<Vec<_>>();
+            diff_fields(fields, &this, &other)?
+        }
+        Body::Enum(variants) if variants.is_empty() => quote!(match *self {}),
+        Body::Enum(variants) => {
+            let mut arms = Vec::new();
+            for variant in variants {
+                let ident = variant.ident;
+                let members = variant
+                    .fields
+                    .iter()
+                    .map(|field| &field.member)
+                    .collect::<Vec<_>>();
+                let this = (0..members.len())
+                    .map(|i| format_ident!("__field{}", i))
+                    .collect::<Vec<_>>();
+                let other = (0..members.len())
+                    .map(|i| format_ident!("__other{}", i))
+                    .collect::<Vec<_>>();
+                let body = diff_fields(
+                    &variant.fields,
+                    &this.iter().map(|b| quote!(*#b)).collect::<Vec<_>>(),
+                    &other.iter().map(|b| quote!(*#b)).collect::<Vec<_>>(),
+                )?;
+                arms.push(quote! {
+                    (
+                        Self::#ident { #(#members: #this),* },
+                        Self::#ident { #(#members: #other),* },
+                    ) => {
+                        #body
+                    }
+                });
+            }
+            // Different variants are compared by their Debug representation
+            // as a whole.
+            let mismatch = if variants.len() > 1 {
+                Some(quote! {
+                    _ => return ::derive_debug::Diff::rendered(self, other),
+                })
+            } else {
+                None
+            };
+            quote! {
+                match (self, other) {
+                    #(#arms)*
+                    #mismatch
+                }
+            }
+        }
+    };
+
+    let helpers = helpers(&input);
+
+    Ok(quote! {
+        impl #impl_generics ::derive_debug::DebugDiff for #ident #ty_generics #where_clause {
+            fn debug_diff(&self, other: &Self) -> ::derive_debug::Diff {
+                #helpers
+                let mut __diff = ::derive_debug::Diff::new();
+                #body
+                __diff
+            }
+        }
+    })
+}
+
+// Each element of `this` and `other` is a place expression for the
+// corresponding field of self and of the other value.
+fn diff_fields(
+    fields: &[Field],
+    this: &[TokenStream],
+    other: &[TokenStream],
+) -> Result<TokenStream> {
+    let mut diffs = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        if field.attrs.skip {
+            continue;
+        }
+        let name = member_name(&field.member);
+        let diff = if is_rendered(field) {
+            let this = debug_value(field, &this[i], fields, this)?;
+            let other = debug_value(field, &other[i], fields, other)?;
+            quote!(::derive_debug::Diff::rendered(#this, #other))
+        } else {
+            let this = &this[i];
+            let other = &other[i];
+            quote!(::derive_debug::DebugDiff::debug_diff(&#this, &#other))
+        };
+        diffs.push(quote! {
+            __diff.field(#name, #diff);
+        });
+    }
+    Ok(quote!(#(#diffs)*))
+}
+
+// Fields with a custom representation in Debug output are compared by that
+// representation rather than recursively. This includes cycle_safe fields,
+// whose recursive comparison would not terminate on a cycle.
+fn is_rendered(field: &Field) -> bool {
+    let attrs = &field.attrs;
+    attrs.format.is_some()
+        || attrs.alt.is_some()
+        || attrs.redact.is_some()
+        || attrs.with.is_some()
+        || attrs.bytes.is_some()
+        || attrs.limit.is_some()
+        || attrs.cycle_safe.is_some()
+}
@@ -0,0 +1,145 @@
+use crate::attr::{self, Display};
+use crate::bound;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Error, Fields, Ident, Member, Result, Type};
+
+pub fn derive(node: &DeriveInput) -> Result<TokenStream> {
+    let attrs = attr::display(&node.attrs)?;
+    let ident = &node.ident;
+
+    // Field types paired with the trait through which they get formatted.
+    let mut inferred = Vec::new();
+
+    let body = match &node.data {
+        Data::Struct(data) => {
+            let members = members(&data.fields);
+            let access = members
+                .iter()
+                .map(|member| quote!(self.#member))
+                .collect::<Vec<_>>();
+            display_fields(ident, &attrs, &data.fields, &access, &mut inferred)?
+        }
+        Data::Enum(data) => {
+            if let Some(format) = &attrs.format {
+                let msg = "put #[display(\"...\")] on each variant of an enum";
+                return Err(Error::new(format.lit.span(), msg));
+            }
+            if let Some(transparent) = &attrs.transparent {
+                let msg = "put #[display(transparent)] on each variant of an enum";
+                return Err(Error::new_spanned(transparent, msg));
+            }
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                let variant_attrs = attr::display(&variant.attrs)?;
+                if let Some(bound) = variant_attrs.bound.as_ref().and_then(|b| b.first()) {
+                    let msg = "#[display(bound = \"...\")] goes on the enum, not a variant";
+                    return Err(Error::new_spanned(bound, msg));
+                }
+                let variant_ident = &variant.ident;
+                let members = members(&variant.fields);
+                let bindings = (0..members.len())
+                    .map(|i| format_ident!("__field{}", i))
+                    .collect::<Vec<_>>();
+                let access = bindings
+                    .iter()
+                    .map(|binding| quote!(*#binding))
+                    .collect::<Vec<_>>();
+                let body = display_fields(
+                    variant_ident,
+                    &variant_attrs,
+                    &variant.fields,
+                    &access,
+                    &mut inferred,
+                )?;
+                arms.push(quote! {
+                    Self::#variant_ident { #(#members: #bindings),* } => #body,
+                });
+            }
+            if arms.is_empty() {
+                quote!(match *self {})
+            } else {
+                quote! {
+                    match self {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+        Data::Union(data) => {
+            let msg = "CustomDisplay does not support unions";
+            return Err(Error::new(data.union_token.span, msg));
+        }
+    };
+
+    let mut generics = node.generics.clone();
+    let bounds = match attrs.bound {
+        Some(bound) => bound,
+        None => bound::infer(&node.generics, &[], inferred),
+    };
+    generics.make_where_clause().predicates.extend(bounds);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Display for #ident #ty_generics #where_clause {
+            fn fmt(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                #body
+            }
+        }
+    })
+}
+
+// Each element of `access` is a place expression for the corresponding field.
+fn display_fields<'a>(
+    ident: &Ident,
+    attrs: &Display,
+    fields: &'a Fields,
+    access: &[TokenStream],
+    inferred: &mut Vec<(&'a Type, TokenStream)>,
+) -> Result<TokenStream> {
+    if let Some(transparent) = &attrs.transparent {
+        if fields.len() != 1 {
+            let msg = "#[display(transparent)] requires exactly one field";
+            return Err(Error::new_spanned(transparent, msg));
+        }
+        let field = fields.iter().next().unwrap();
+        inferred.push((&field.ty, quote!(::core::fmt::Display)));
+        let access = &access[0];
+        return Ok(quote!(::core::fmt::Display::fmt(&#access, formatter)));
+    }
+
+    let format = match &attrs.format {
+        Some(format) => format,
+        None if fields.is_empty() => {
+            let name = ident.to_string();
+            return Ok(quote!(formatter.write_str(#name)));
+        }
+        None => {
+            let msg = "missing #[display(\"...\")] or #[display(transparent)] attribute";
+            return Err(Error::new(ident.span(), msg));
+        }
+    };
+
+    let members = members(fields);
+    for (member, trait_name) in &format.traits {
+        if let Some(i) = members.iter().position(|m| m == member) {
+            let field = fields.iter().nth(i).unwrap();
+            let trait_name = Ident::new(trait_name, Span::call_site());
+            inferred.push((&field.ty, quote!(::core::fmt::#trait_name)));
+        }
+    }
+    let members = members.iter().collect::<Vec<_>>();
+    let args = format.args(None, &members, access)?;
+    Ok(quote!(formatter.write_fmt(#args)))
+}
+
+fn members(fields: &Fields) -> Vec<Member> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(i.into()),
+        })
+        .collect()
+}
@@ -0,0 +1,726 @@
+use crate::ast::{Body, Field, Input, Style, Variant};
+use crate::attr::Bytes;
+use crate::bound;
+use crate::fmt::Format;
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::spanned::Spanned;
+use syn::{DeriveInput, Error, Ident, Member, PathArguments, Result, Type};
+
+pub fn derive(node: &DeriveInput) -> Result<TokenStream> {
+    let input = Input::from_syn(node)?;
+
+    let mut generics = node.generics.clone();
+    let bounds = match &input.attrs.bound {
+        Some(bound) => bound.clone(),
+        None => {
+            let mut fields = Vec::new();
+            for siblings in siblings(&input) {
+                let inferred = siblings.iter().filter(|field| field.attrs.bound.is_none());
+                for field in inferred.filter(|field| !field.attrs.skip) {
+                    let attrs = &field.attrs;
+                    if attrs.with.is_none() && attrs.bytes.is_none() && attrs.redact.is_none() {
+                        if attrs.format.is_none() {
+                            fields.push((field.ty, quote!(::core::fmt::Debug)));
+                        }
+                        for format in attrs.format.iter().chain(&attrs.alt) {
+                            format_bounds(format, Some(field), siblings, &mut fields);
+                        }
+                    }
+                    if input.attrs.skip_defaults && field.attrs.skip_if.is_none() {
+                        let bound = quote!(::core::default::Default + ::core::cmp::PartialEq);
+                        fields.push((field.ty, bound));
+                    }
+                }
+            }
+            if let Body::Struct(_style, siblings) = &input.data {
+                for format in input.attrs.format.iter().chain(&input.attrs.alt) {
+                    format_bounds(format, None, siblings, &mut fields);
+                }
+            }
+            let mut bounds =
+                bound::infer(&node.generics, &input.attrs.transparent_wrappers, fields);
+            for field in input.fields() {
+                if let Some(bound) = &field.attrs.bound {
+                    bounds.extend(bound.iter().cloned());
+                }
+            }
+            bounds
+        }
+    };
+    generics.make_where_clause().predicates.extend(bounds);
+
+    let ident = input.ident;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    // A remote mirror describes how to print some other type, so the
+    // generated code reads the fields of that type instead of its own.
+    let (name, this, path) = match &input.attrs.remote {
+        Some(remote) => {
+            let mut path = remote.clone();
+            let last = path.segments.last_mut().unwrap();
+            let name = last.ident.clone();
+            last.arguments = PathArguments::None;
+            (name, quote!(value), quote!(#path))
+        }
+        None => (ident.clone(), quote!(self), quote!(Self)),
+    };
+    let body = debug_body(&input, &name, &this, &path)?;
+
+    // The guard is held until the end of fmt, so that the Debug impls of the
+    // fields run at one level deeper.
+    let depth = input.attrs.max_depth.map(|max_depth| {
+        quote! {
+            let __depth = match ::derive_debug::__private::Depth::enter(#max_depth) {
+                ::core::option::Option::Some(depth) => depth,
+                ::core::option::Option::None => return formatter.write_str(".."),
+            };
+        }
+    });
+    let helpers = helpers(&input);
+
+    if let Some(remote) = &input.attrs.remote {
+        let vis = &node.vis;
+        let check = remote_check(&input, &path);
+        let mirror = mirror_use(&input);
+        return Ok(quote! {
+            impl #impl_generics #ident #ty_generics #where_clause {
+                #vis fn fmt_remote(
+                    value: &#remote,
+                    formatter: &mut ::core::fmt::Formatter,
+                ) -> ::core::fmt::Result {
+                    #check
+                    #mirror
+                    #helpers
+                    #depth
+                    #body
+                }
+            }
+        });
+    }
+
+    let for_each_body = for_each_field(&input)?;
+
+    Ok(quote! {
+        impl #impl_generics ::core::fmt::Debug for #ident #ty_generics #where_clause {
+            fn fmt(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                #helpers
+                #depth
+                #body
+            }
+        }
+
+        impl #impl_generics ::derive_debug::DebugFields for #ident #ty_generics #where_clause {
+            fn for_each_field(
+                &self,
+                __f: &mut dyn ::core::ops::FnMut(&'static str, &dyn ::core::fmt::Debug),
+            ) {
+                #helpers
+                #for_each_body
+            }
+        }
+    })
+}
+
+// The fields of the struct, or of each variant of the enum, which are the ones
+// that a format string on one of them can refer to.
+fn siblings<'i, 'a>(input: &'i Input<'a>) -> Vec<&'i [Field<'a>]> {
+    match &input.data {
+        Body::Struct(_style, fields) => vec![fields],
+        Body::Enum(variants) => variants.iter().map(|variant| &*variant.fields).collect(),
+    }
+}
+
+// Adds the formatting trait of each placeholder in `format` for the type of
+// the field it refers to: `field`, which the attribute is on, for `{}`, and
+// one of `siblings` for `{self.other}`.
+fn format_bounds<'a>(
+    format: &Format,
+    field: Option<&Field<'a>>,
+    siblings: &[Field<'a>],
+    bounds: &mut Vec<(&'a Type, TokenStream)>,
+) {
+    let value = field
+        .into_iter()
+        .flat_map(|field| format.value_traits.iter().map(move |name| (field, name)));
+    let others = format.traits.iter().filter_map(|(member, name)| {
+        let other = siblings.iter().find(|other| other.member == *member)?;
+        Some((other, name))
+    });
+    for (field, name) in value.chain(others) {
+        if field.attrs.bound.is_none() {
+            let name = Ident::new(name, Span::call_site());
+            bounds.push((field.ty, quote!(::core::fmt::#name)));
+        }
+    }
+}
+
+// The Debug output of `this`, whose type is named by `path` in patterns and
+// by `name` in the output.
+fn debug_body(
+    input: &Input,
+    name: &Ident,
+    this: &TokenStream,
+    path: &TokenStream,
+) -> Result<TokenStream> {
+    Ok(match &input.data {
+        Body::Struct(style, fields) => {
+            let access = fields
+                .iter()
+                .map(|field| {
+                    let member = &field.member;
+                    quote!(#this.#member)
+                })
+                .collect::<Vec<_>>();
+            let body = debug_fields(
+                name,
+                *style,
+                fields,
+                &access,
+                input.attrs.skip_defaults,
+                input.attrs.non_exhaustive.is_some(),
+            )?;
+            let format = match &input.attrs.format {
+                Some(format) => {
+                    let args = format.args(None, &members(fields), &access)?;
+                    quote!(formatter.write_fmt(#args))
+                }
+                None => body,
+            };
+            match &input.attrs.alt {
+                Some(alt) => {
+                    let args = alt.args(None, &members(fields), &access)?;
+                    quote! {
+                        if formatter.alternate() {
+                            formatter.write_fmt(#args)
+                        } else {
+                            #format
+                        }
+                    }
+                }
+                None => format,
+            }
+        }
+        Body::Enum(variants) => {
+            if let Some(format) = input.attrs.format.as_ref().or(input.attrs.alt.as_ref()) {
+                let msg = "a format string for the whole type is only supported on structs";
+                return Err(Error::new(format.lit.span(), msg));
+            }
+            if let Some(non_exhaustive) = &input.attrs.non_exhaustive {
+                let msg = "put #[debug(non_exhaustive)] on the variants of an enum";
+                return Err(Error::new_spanned(non_exhaustive, msg));
+            }
+            let arms = variants
+                .iter()
+                .map(|variant| debug_variant(variant, path, input.attrs.skip_defaults))
+                .collect::<Result<Vec<_>>>()?;
+            if arms.is_empty() {
+                quote!(match *#this {})
+            } else {
+                quote! {
+                    match #this {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+    })
+}
+
+// Refers to each field of the remote type with the type the mirror gives it,
+// so that a mismatch is reported at the mirror's field rather than somewhere
+// in the generated code. Unless the mirror or its variant is marked
+// #[debug(non_exhaustive)], which ends the output with `..`, the mirror also
+// has to list every field, since output without `..` claims to be complete.
+// For a struct that is checked by building one in a closure that never runs,
+// for a variant by matching it without `..`.
+fn remote_check(input: &Input, path: &TokenStream) -> TokenStream {
+    let check = |field: &Field, value: &dyn ToTokens| {
+        let ty = field.ty;
+        quote_spanned!(ty.span()=> let _: &#ty = #value;)
+    };
+    match &input.data {
+        Body::Struct(_style, fields) => {
+            let checks = fields.iter().map(|field| {
+                let member = &field.member;
+                check(field, &quote_spanned!(field.ty.span()=> &value.#member))
+            });
+            let exhaustive = input.attrs.non_exhaustive.is_none().then(|| {
+                let members = fields.iter().map(|field| &field.member);
+                let init = quote! {
+                    #[allow(unreachable_code)]
+                    let _ = || #path { #(#members: ::core::unreachable!(),)* };
+                };
+                respan(init, input.ident.span())
+            });
+            quote!(#(#checks)* #exhaustive)
+        }
+        Body::Enum(variants) => {
+            let arms = variants.iter().map(|variant| {
+                let ident = variant.ident;
+                let members = variant.fields.iter().map(|field| &field.member);
+                let bindings = (0..variant.fields.len())
+                    .map(|i| format_ident!("__field{}", i))
+                    .collect::<Vec<_>>();
+                let checks = variant
+                    .fields
+                    .iter()
+                    .zip(&bindings)
+                    .map(|(field, binding)| check(field, binding));
+                let rest = variant.attrs.non_exhaustive.then(|| quote!(..));
+                quote! {
+                    #path::#ident { #(#members: #bindings,)* #rest } => {
+                        #(#checks)*
+                    }
+                }
+            });
+            quote! {
+                #[allow(unreachable_patterns)]
+                match value {
+                    #(#arms)*
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+// The mirror itself is never constructed or read, since fmt_remote prints the
+// remote value, which would make its fields and variants dead code. A closure
+// that is never called uses them instead.
+fn mirror_use(input: &Input) -> TokenStream {
+    match &input.data {
+        Body::Struct(_style, fields) => {
+            let members = fields.iter().map(|field| &field.member);
+            quote! {
+                let _ = |mirror: &Self| {
+                    #(let _ = &mirror.#members;)*
+                };
+            }
+        }
+        Body::Enum(variants) if variants.is_empty() => TokenStream::new(),
+        Body::Enum(variants) => {
+            let values = variants.iter().map(|variant| {
+                let ident = variant.ident;
+                let members = variant.fields.iter().map(|field| &field.member);
+                quote!(Self::#ident { #(#members: ::core::unreachable!(),)* })
+            });
+            let arms = variants.iter().map(|variant| {
+                let ident = variant.ident;
+                let members = variant.fields.iter().map(|field| &field.member);
+                let bindings = (0..variant.fields.len())
+                    .map(|i| format_ident!("__field{}", i))
+                    .collect::<Vec<_>>();
+                quote! {
+                    Self::#ident { #(#members: #bindings,)* } => {
+                        #(let _ = #bindings;)*
+                    }
+                }
+            });
+            quote! {
+                #[allow(unreachable_code)]
+                let _ = || [#(#values,)*];
+                let _ = |mirror: &Self| match mirror {
+                    #(#arms)*
+                };
+            }
+        }
+    }
+}
+
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if let TokenTree::Group(group) = &token {
+                let stream = respan(group.stream(), span);
+                token = TokenTree::Group(Group::new(group.delimiter(), stream));
+            }
+            token.set_span(span);
+            token
+        })
+        .collect()
+}
+
+// Passes each field that appears in the Debug output to the DebugFields
+// callback, with the same representation.
+fn for_each_field(input: &Input) -> Result<TokenStream> {
+    let skip_defaults = input.attrs.skip_defaults;
+    match &input.data {
+        Body::Struct(_style, fields) => {
+            let access = fields
+                .iter()
+                .map(|field| {
+                    let member = &field.member;
+                    quote!(self.#member)
+                })
+                .collect::<Vec<_>>();
+            for_each_entry(fields, &access, skip_defaults)
+        }
+        Body::Enum(variants) if variants.is_empty() => Ok(quote!(match *self {})),
+        Body::Enum(variants) => {
+            let mut arms = Vec::new();
+            for variant in variants {
+                let ident = variant.ident;
+                let members = variant.fields.iter().map(|field| &field.member);
+                let bindings = (0..variant.fields.len())
+                    .map(|i| format_ident!("__field{}", i))
+                    .collect::<Vec<_>>();
+                let access = bindings
+                    .iter()
+                    .map(|binding| quote!(*#binding))
+                    .collect::<Vec<_>>();
+                let body = for_each_entry(&variant.fields, &access, skip_defaults)?;
+                arms.push(quote! {
+                    Self::#ident { #(#members: #bindings),* } => {
+                        #body
+                    }
+                });
+            }
+            Ok(quote! {
+                match self {
+                    #(#arms)*
+                }
+            })
+        }
+    }
+}
+
+fn for_each_entry(
+    fields: &[Field],
+    access: &[TokenStream],
+    skip_defaults: bool,
+) -> Result<TokenStream> {
+    let calls = entries(fields, access, skip_defaults)?
+        .into_iter()
+        .map(|entry| {
+            let name = entry.name;
+            let debug = entry.debug;
+            match entry.hide {
+                Some(hide) => quote! {
+                    if !(#hide) {
+                        __f(#name, #debug);
+                    }
+                },
+                None => quote! {
+                    __f(#name, #debug);
+                },
+            }
+        });
+    Ok(quote!(#(#calls)*))
+}
+
+// Local definitions of the adapter types that field values get wrapped in.
+pub fn helpers(input: &Input) -> TokenStream {
+    let mut helpers = TokenStream::new();
+    let shown = || input.fields().filter(|field| !field.attrs.skip);
+    if shown().any(|field| field.attrs.with.is_some()) {
+        helpers.extend(debug_with_helper());
+    }
+    if shown().any(|field| field.attrs.alt.is_some()) {
+        helpers.extend(debug_alt_helper());
+    }
+    if shown().any(|field| field.attrs.bytes.is_some()) {
+        helpers.extend(debug_bytes_helper());
+    }
+    if shown().any(|field| field.attrs.limit.is_some() && field.attrs.bytes.is_none()) {
+        helpers.extend(debug_limit_helper());
+    }
+    helpers
+}
+
+fn debug_variant(
+    variant: &Variant,
+    path: &TokenStream,
+    skip_defaults: bool,
+) -> Result<TokenStream> {
+    let ident = variant.ident;
+    let members = variant.fields.iter().map(|field| &field.member);
+    let bindings = (0..variant.fields.len())
+        .map(|i| format_ident!("__field{}", i))
+        .collect::<Vec<_>>();
+    let access = bindings
+        .iter()
+        .map(|binding| quote!(*#binding))
+        .collect::<Vec<_>>();
+    let body = debug_fields(
+        ident,
+        variant.style,
+        &variant.fields,
+        &access,
+        skip_defaults,
+        variant.attrs.non_exhaustive,
+    )?;
+    let rest = variant.attrs.non_exhaustive.then(|| quote!(..));
+    Ok(quote! {
+        #path::#ident { #(#members: #bindings,)* #rest } => #body,
+    })
+}
+
+// Each element of `access` is a place expression for the corresponding field.
+fn debug_fields(
+    ident: &Ident,
+    style: Style,
+    fields: &[Field],
+    access: &[TokenStream],
+    skip_defaults: bool,
+    non_exhaustive: bool,
+) -> Result<TokenStream> {
+    let name = ident.to_string();
+    if let Style::Unit = style {
+        if non_exhaustive {
+            return Ok(quote!(formatter.debug_struct(#name).finish_non_exhaustive()));
+        }
+        return Ok(quote!(formatter.write_str(#name)));
+    }
+
+    let entries = entries(fields, access, skip_defaults)?;
+    let conditional = entries.iter().any(|entry| entry.hide.is_some());
+    let entries = entries
+        .into_iter()
+        .map(|entry| {
+            let debug = entry.debug;
+            let field = match style {
+                Style::Named => {
+                    let name = entry.name;
+                    quote!(.field(#name, #debug))
+                }
+                _ => quote!(.field(#debug)),
+            };
+            (field, entry.hide)
+        })
+        .collect::<Vec<_>>();
+
+    let builder = match style {
+        Style::Named => quote!(formatter.debug_struct(#name)),
+        _ => quote!(formatter.debug_tuple(#name)),
+    };
+    let skipped = non_exhaustive || fields.iter().any(|field| field.attrs.skip);
+
+    if !conditional {
+        let entries = entries.iter().map(|(entry, _hide)| entry);
+        let finish = if skipped {
+            quote!(finish_non_exhaustive)
+        } else {
+            quote!(finish)
+        };
+        return Ok(quote! {
+            #builder
+                #(#entries)*
+                .#finish()
+        });
+    }
+
+    let entries = entries.iter().map(|(entry, hide)| match hide {
+        Some(hide) => quote! {
+            if #hide {
+                __non_exhaustive = true;
+            } else {
+                __debug #entry;
+            }
+        },
+        None => quote! {
+            __debug #entry;
+        },
+    });
+    Ok(quote! {{
+        let mut __debug = #builder;
+        let mut __non_exhaustive = #skipped;
+        #(#entries)*
+        if __non_exhaustive {
+            __debug.finish_non_exhaustive()
+        } else {
+            __debug.finish()
+        }
+    }})
+}
+
+struct Entry {
+    name: String,
+    debug: TokenStream,
+    // Condition under which the field is left out of the output.
+    hide: Option<TokenStream>,
+}
+
+// The fields that appear in Debug output, in order.
+fn entries(fields: &[Field], access: &[TokenStream], skip_defaults: bool) -> Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    for (field, value) in fields.iter().zip(access) {
+        if field.attrs.skip {
+            continue;
+        }
+        let hide = if let Some(skip_if) = &field.attrs.skip_if {
+            Some(quote!(#skip_if(&#value)))
+        } else if skip_defaults {
+            let ty = field.ty;
+            Some(quote!(#value == <#ty as ::core::default::Default>::default()))
+        } else {
+            None
+        };
+        entries.push(Entry {
+            name: member_name(&field.member),
+            debug: debug_value(field, value, fields, access)?,
+            hide,
+        });
+    }
+    Ok(entries)
+}
+
+pub fn debug_value(
+    field: &Field,
+    value: &TokenStream,
+    fields: &[Field],
+    access: &[TokenStream],
+) -> Result<TokenStream> {
+    let debug = if field.attrs.redact.is_some() {
+        quote!(&::core::format_args!("[REDACTED]"))
+    } else if let Some(format) = &field.attrs.format {
+        let args = format.args(Some(value), &members(fields), access)?;
+        quote!(&#args)
+    } else if let Some(with) = &field.attrs.with {
+        quote!(&__DebugWith(&#value, #with))
+    } else if let Some(bytes) = field.attrs.bytes {
+        let hex = match bytes {
+            Bytes::Hex => true,
+            Bytes::Ascii => false,
+        };
+        let limit = match field.attrs.limit {
+            Some(limit) => quote!(::core::option::Option::Some(#limit)),
+            None => quote!(::core::option::Option::None),
+        };
+        quote! {
+            &__DebugBytes(::core::convert::AsRef::<[u8]>::as_ref(&#value), #hex, #limit)
+        }
+    } else if let Some(limit) = field.attrs.limit {
+        quote!(&__DebugLimit(&#value, #limit))
+    } else if field.attrs.cycle_safe.is_some() {
+        quote!(&::derive_debug::__private::CycleSafe(&#value))
+    } else {
+        quote!(&#value)
+    };
+    Ok(match &field.attrs.alt {
+        Some(alt) => {
+            let args = alt.args(Some(value), &members(fields), access)?;
+            quote!(&__DebugAlt(#debug, &#args))
+        }
+        None => debug,
+    })
+}
+
+pub fn members<'a>(fields: &'a [Field]) -> Vec<&'a Member> {
+    fields.iter().map(|field| &field.member).collect()
+}
+
+pub fn member_name(member: &Member) -> String {
+    match member {
+        Member::Named(ident) => ident.to_string(),
+        Member::Unnamed(index) => index.index.to_string(),
+    }
+}
+
+// Adapts a `fn(&T, &mut Formatter) -> fmt::Result` from #[debug(with = "...")]
+// into a value that DebugStruct::field can print.
+fn debug_with_helper() -> TokenStream {
+    quote! {
+        struct __DebugWith<'__a, __T: ?::core::marker::Sized>(
+            &'__a __T,
+            fn(&__T, &mut ::core::fmt::Formatter) -> ::core::fmt::Result,
+        );
+
+        impl<'__a, __T: ?::core::marker::Sized> ::core::fmt::Debug for __DebugWith<'__a, __T> {
+            fn fmt(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                (self.1)(self.0, formatter)
+            }
+        }
+    }
+}
+
+// Chooses between the regular and the #[debug(alt = "...")] representation of
+// a field depending on whether the caller asked for {:#?}.
+fn debug_alt_helper() -> TokenStream {
+    quote! {
+        struct __DebugAlt<'__a>(
+            &'__a dyn ::core::fmt::Debug,
+            &'__a dyn ::core::fmt::Debug,
+        );
+
+        impl<'__a> ::core::fmt::Debug for __DebugAlt<'__a> {
+            fn fmt(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                if formatter.alternate() {
+                    ::core::fmt::Debug::fmt(self.1, formatter)
+                } else {
+                    ::core::fmt::Debug::fmt(self.0, formatter)
+                }
+            }
+        }
+    }
+}
+
+// Prints the first elements of an iterable from #[debug(limit = N)] as a list,
+// followed by a count of the elements that were left out. The count is only
+// given when the iterator knows its exact length; otherwise a single element
+// is looked at to tell whether to end with `...`, so that formatting does not
+// walk the rest of a large or unbounded iterable.
+fn debug_limit_helper() -> TokenStream {
+    quote! {
+        struct __DebugLimit<'__a, __T: ?::core::marker::Sized>(&'__a __T, usize);
+
+        impl<'__a, __T: ?::core::marker::Sized> ::core::fmt::Debug for __DebugLimit<'__a, __T>
+        where
+            &'__a __T: ::core::iter::IntoIterator,
+            <&'__a __T as ::core::iter::IntoIterator>::Item: ::core::fmt::Debug,
+        {
+            fn fmt(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                let mut iter = ::core::iter::IntoIterator::into_iter(self.0);
+                let mut list = formatter.debug_list();
+                list.entries(::core::iter::Iterator::by_ref(&mut iter).take(self.1));
+                let (lower, upper) = ::core::iter::Iterator::size_hint(&iter);
+                if upper == ::core::option::Option::Some(lower) {
+                    if lower > 0 {
+                        list.entry(&::core::format_args!("... ({} more)", lower));
+                    }
+                } else if ::core::iter::Iterator::next(&mut iter).is_some() {
+                    list.entry(&::core::format_args!("..."));
+                }
+                list.finish()
+            }
+        }
+    }
+}
+
+// Prints a byte string from #[debug(hex)] as contiguous hex digits, or from
+// #[debug(ascii)] as an escaped b"..." literal, optionally truncated by
+// #[debug(limit = N)].
+fn debug_bytes_helper() -> TokenStream {
+    quote! {
+        struct __DebugBytes<'__a>(&'__a [u8], bool, ::core::option::Option<usize>);
+
+        impl<'__a> ::core::fmt::Debug for __DebugBytes<'__a> {
+            fn fmt(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                let len = match self.2 {
+                    ::core::option::Option::Some(limit) if limit < self.0.len() => limit,
+                    _ => self.0.len(),
+                };
+                if !self.1 {
+                    formatter.write_str("b\"")?;
+                }
+                for &byte in &self.0[..len] {
+                    if self.1 {
+                        ::core::write!(formatter, "{:02x}", byte)?;
+                    } else {
+                        for escaped in ::core::ascii::escape_default(byte) {
+                            ::core::fmt::Write::write_char(formatter, escaped as char)?;
+                        }
+                    }
+                }
+                if !self.1 {
+                    formatter.write_str("\"")?;
+                }
+                if len < self.0.len() {
+                    ::core::write!(formatter, " ... ({} more)", self.0.len() - len)?;
+                }
+                ::core::result::Result::Ok(())
+            }
+        }
+    }
+}
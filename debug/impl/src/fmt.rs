@@ -0,0 +1,146 @@
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
+use syn::{Error, LitStr, Member, Result};
+
+// A format string from an attribute, in which every placeholder that refers to
+// a field has been rewritten to refer to a named argument of format_args.
+pub struct Format {
+    pub lit: LitStr,
+    // Whether the string contains a placeholder like `{}` or `{:08b}` that
+    // refers to the value of the field the attribute is on.
+    pub positional: bool,
+    // Fields referred to by a placeholder, in order of first appearance.
+    pub fields: Vec<Member>,
+    // The formatting trait each placeholder uses for the field it refers to,
+    // like `LowerHex` for `{self.bitmask:#x}`.
+    pub traits: Vec<(Member, &'static str)>,
+    // The formatting traits of the placeholders that refer to the value of
+    // the field the attribute is on, like `Binary` for `{:08b}`.
+    pub value_traits: Vec<&'static str>,
+}
+
+impl Format {
+    // Debug attributes refer to fields as `{self.field}`.
+    pub fn parse(lit: &LitStr) -> Result<Self> {
+        Format::parse_impl(lit, false)
+    }
+
+    // Display attributes refer to fields as `{field}`, or `{0}` in a tuple
+    // struct, like thiserror.
+    pub fn parse_bare(lit: &LitStr) -> Result<Self> {
+        Format::parse_impl(lit, true)
+    }
+
+    fn parse_impl(lit: &LitStr, bare: bool) -> Result<Self> {
+        let value = lit.value();
+        let mut read = value.as_str();
+        let mut out = String::new();
+        let mut positional = false;
+        let mut fields = Vec::new();
+        let mut traits = Vec::new();
+        let mut value_traits = Vec::new();
+
+        while let Some(i) = read.find(&['{', '}'][..]) {
+            out += &read[..i + 1];
+            let brace = read.as_bytes()[i];
+            read = &read[i + 1..];
+            if brace == b'}' || read.starts_with('{') {
+                // Escaped brace, or an unmatched one which format_args will
+                // report.
+                if read.starts_with(brace as char) {
+                    out.push(brace as char);
+                    read = &read[1..];
+                }
+                continue;
+            }
+            let end = read.find(&['}', ':'][..]).unwrap_or(read.len());
+            let arg = read[..end].trim();
+            read = &read[end..];
+            let spec = read[..read.find('}').unwrap_or(read.len())].trim_start_matches(':');
+            let member = match arg.strip_prefix("self.") {
+                Some(member) => member,
+                None if bare && !arg.is_empty() => arg,
+                None => {
+                    if arg.is_empty() || arg.bytes().all(|b| b.is_ascii_digit()) {
+                        positional = true;
+                        if !value_traits.contains(&format_trait(spec)) {
+                            value_traits.push(format_trait(spec));
+                        }
+                    }
+                    out += arg;
+                    continue;
+                }
+            };
+            let member = syn::parse_str::<Member>(member).map_err(|_| {
+                let msg = format!("expected a field name in format string, found `{}`", arg);
+                Error::new(lit.span(), msg)
+            })?;
+            out += &binding(&member).to_string();
+            let pair = (member.clone(), format_trait(spec));
+            if !traits.contains(&pair) {
+                traits.push(pair);
+            }
+            if !fields.contains(&member) {
+                fields.push(member);
+            }
+        }
+        out += read;
+
+        Ok(Format {
+            lit: LitStr::new(&out, lit.span()),
+            positional,
+            fields,
+            traits,
+            value_traits,
+        })
+    }
+
+    // Expands to format_args!, passing `value` as the positional argument and
+    // each referenced field as a named argument. Each element of `access` is a
+    // place expression for the corresponding element of `members`.
+    pub fn args(
+        &self,
+        value: Option<&TokenStream>,
+        members: &[&Member],
+        access: &[TokenStream],
+    ) -> Result<TokenStream> {
+        let lit = &self.lit;
+        let mut args = Vec::new();
+        if let (true, Some(value)) = (self.positional, value) {
+            args.push(value.clone());
+        }
+        for member in &self.fields {
+            let i = match members.iter().position(|m| *m == member) {
+                Some(i) => i,
+                None => {
+                    let msg = format!("no field `{}` to refer to", member.to_token_stream());
+                    return Err(Error::new(lit.span(), msg));
+                }
+            };
+            let binding = binding(member);
+            let access = &access[i];
+            args.push(quote!(#binding = #access));
+        }
+        Ok(quote!(::core::format_args!(#lit #(, #args)*)))
+    }
+}
+
+// The name of the format_args argument that holds `self.#member`.
+fn binding(member: &Member) -> Ident {
+    format_ident!("__self_{}", member)
+}
+
+fn format_trait(spec: &str) -> &'static str {
+    if spec.ends_with('?') {
+        return "Debug";
+    }
+    match spec.chars().last() {
+        Some('x') => "LowerHex",
+        Some('X') => "UpperHex",
+        Some('o') => "Octal",
+        Some('b') => "Binary",
+        Some('e') => "LowerExp",
+        Some('E') => "UpperExp",
+        _ => "Display",
+    }
+}
@@ -0,0 +1,323 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{self, Debug, Display};
+use std::hash::Hash;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Field by field comparison of two values of the same type.
+///
+/// Implemented by `#[derive(DebugDiff)]`, which recurses into every field
+/// whose type implements DebugDiff too. Primitives and strings are compared
+/// as a whole, while collections report the individual elements that differ.
+pub trait DebugDiff: Debug {
+    fn debug_diff(&self, other: &Self) -> Diff;
+}
+
+/// The differences found by [`DebugDiff::debug_diff`].
+///
+/// Displays as one line per difference, like `args[1]: "build" -> "test"`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Diff {
+    changes: Vec<Change>,
+}
+
+/// A single difference within a [`Diff`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change {
+    path: Vec<Segment>,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Field(String),
+    Index(String),
+}
+
+impl Diff {
+    pub fn new() -> Self {
+        Diff::default()
+    }
+
+    /// Compares two values with PartialEq, printing them with Debug if they
+    /// differ.
+    pub fn leaf<T: ?Sized + PartialEq + Debug>(before: &T, after: &T) -> Self {
+        if before == after {
+            Diff::new()
+        } else {
+            Diff::change(Some(&before), Some(&after))
+        }
+    }
+
+    /// Compares two values by their Debug representation.
+    pub fn rendered(before: &dyn Debug, after: &dyn Debug) -> Self {
+        let before = format!("{:?}", before);
+        let after = format!("{:?}", after);
+        if before == after {
+            Diff::new()
+        } else {
+            Diff {
+                changes: vec![Change {
+                    path: Vec::new(),
+                    before: Some(before),
+                    after: Some(after),
+                }],
+            }
+        }
+    }
+
+    /// A difference in which either side may be absent, such as an element
+    /// that only one of two vectors has.
+    pub fn change(before: Option<&dyn Debug>, after: Option<&dyn Debug>) -> Self {
+        Diff {
+            changes: vec![Change {
+                path: Vec::new(),
+                before: before.map(|before| format!("{:?}", before)),
+                after: after.map(|after| format!("{:?}", after)),
+            }],
+        }
+    }
+
+    /// Adds the differences within a field, prefixing their path with the
+    /// field name.
+    pub fn field(&mut self, name: &str, diff: Diff) {
+        self.nest(Segment::Field(name.to_owned()), diff);
+    }
+
+    /// Adds the differences within an element of a collection, prefixing
+    /// their path with the Debug representation of the index or key.
+    pub fn index(&mut self, index: &dyn Debug, diff: Diff) {
+        self.nest(Segment::Index(format!("{:?}", index)), diff);
+    }
+
+    fn nest(&mut self, segment: Segment, diff: Diff) {
+        for mut change in diff.changes {
+            change.path.insert(0, segment.clone());
+            self.changes.push(change);
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+}
+
+impl Change {
+    /// The location of the difference, like `args[1]` or `config.name`.
+    pub fn path(&self) -> String {
+        let mut path = String::new();
+        for segment in &self.path {
+            match segment {
+                Segment::Field(name) => {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+                    path += name;
+                }
+                Segment::Index(index) => {
+                    path.push('[');
+                    path += index;
+                    path.push(']');
+                }
+            }
+        }
+        path
+    }
+
+    /// The Debug representation of the old value, or None if it is absent.
+    pub fn before(&self) -> Option<&str> {
+        self.before.as_deref()
+    }
+
+    /// The Debug representation of the new value, or None if it is absent.
+    pub fn after(&self) -> Option<&str> {
+        self.after.as_deref()
+    }
+}
+
+impl Display for Diff {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (i, change) in self.changes.iter().enumerate() {
+            if i > 0 {
+                formatter.write_str("\n")?;
+            }
+            Display::fmt(change, formatter)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if !self.path.is_empty() {
+            write!(formatter, "{}: ", self.path())?;
+        }
+        let before = self.before().unwrap_or("<absent>");
+        let after = self.after().unwrap_or("<absent>");
+        write!(formatter, "{} -> {}", before, after)
+    }
+}
+
+macro_rules! leaf {
+    ($($ty:ty),*) => {
+        $(
+            impl DebugDiff for $ty {
+                fn debug_diff(&self, other: &Self) -> Diff {
+                    Diff::leaf(self, other)
+                }
+            }
+        )*
+    };
+}
+
+leaf! {
+    bool, char, str, String, (),
+    i8, i16, i32, i64, i128, isize,
+    u8, u16, u32, u64, u128, usize,
+    f32, f64
+}
+
+macro_rules! deref {
+    ($($ty:ident)*) => {
+        $(
+            impl<T: ?Sized + DebugDiff> DebugDiff for $ty<T> {
+                fn debug_diff(&self, other: &Self) -> Diff {
+                    T::debug_diff(self, other)
+                }
+            }
+        )*
+    };
+}
+
+deref!(Box Rc Arc);
+
+impl<T: ?Sized + DebugDiff> DebugDiff for &T {
+    fn debug_diff(&self, other: &Self) -> Diff {
+        T::debug_diff(self, other)
+    }
+}
+
+impl<T: DebugDiff> DebugDiff for Option<T> {
+    fn debug_diff(&self, other: &Self) -> Diff {
+        match (self, other) {
+            (Some(before), Some(after)) => before.debug_diff(after),
+            (None, None) => Diff::new(),
+            _ => Diff::change(Some(self), Some(other)),
+        }
+    }
+}
+
+impl<T: DebugDiff> DebugDiff for [T] {
+    fn debug_diff(&self, other: &Self) -> Diff {
+        let mut diff = Diff::new();
+        for i in 0..self.len().max(other.len()) {
+            let element = match (self.get(i), other.get(i)) {
+                (Some(before), Some(after)) => before.debug_diff(after),
+                (before, after) => Diff::change(
+                    before.map(|before| before as &dyn Debug),
+                    after.map(|after| after as &dyn Debug),
+                ),
+            };
+            diff.index(&i, element);
+        }
+        diff
+    }
+}
+
+impl<T: DebugDiff> DebugDiff for Vec<T> {
+    fn debug_diff(&self, other: &Self) -> Diff {
+        self.as_slice().debug_diff(other)
+    }
+}
+
+impl<T: DebugDiff, const N: usize> DebugDiff for [T; N] {
+    fn debug_diff(&self, other: &Self) -> Diff {
+        self[..].debug_diff(&other[..])
+    }
+}
+
+impl<K, V> DebugDiff for BTreeMap<K, V>
+where
+    K: Debug + Ord,
+    V: DebugDiff,
+{
+    fn debug_diff(&self, other: &Self) -> Diff {
+        let mut keys = self.keys().chain(other.keys()).collect::<Vec<_>>();
+        keys.sort();
+        keys.dedup();
+        diff_maps(keys, |key| self.get(key), |key| other.get(key))
+    }
+}
+
+impl<K, V, S> DebugDiff for HashMap<K, V, S>
+where
+    K: Debug + Eq + Hash,
+    V: DebugDiff,
+    S: std::hash::BuildHasher,
+{
+    fn debug_diff(&self, other: &Self) -> Diff {
+        // Sorted by their Debug representation so that the order of the
+        // differences does not depend on the hasher.
+        let mut keys = self.keys().collect::<Vec<_>>();
+        keys.extend(other.keys().filter(|key| !self.contains_key(key)));
+        keys.sort_by_cached_key(|key| format!("{:?}", key));
+        diff_maps(keys, |key| self.get(key), |key| other.get(key))
+    }
+}
+
+fn diff_maps<'a, K, V>(
+    keys: Vec<&'a K>,
+    before: impl Fn(&K) -> Option<&'a V>,
+    after: impl Fn(&K) -> Option<&'a V>,
+) -> Diff
+where
+    K: Debug + 'a,
+    V: DebugDiff + 'a,
+{
+    let mut diff = Diff::new();
+    for key in keys {
+        let entry = match (before(key), after(key)) {
+            (Some(before), Some(after)) => before.debug_diff(after),
+            (before, after) => Diff::change(
+                before.map(|before| before as &dyn Debug),
+                after.map(|after| after as &dyn Debug),
+            ),
+        };
+        diff.index(key, entry);
+    }
+    diff
+}
+
+macro_rules! tuple {
+    ($(($($name:ident $index:tt)*))*) => {
+        $(
+            impl<$($name: DebugDiff),*> DebugDiff for ($($name,)*) {
+                fn debug_diff(&self, other: &Self) -> Diff {
+                    let mut diff = Diff::new();
+                    $(
+                        diff.field(stringify!($index), self.$index.debug_diff(&other.$index));
+                    )*
+                    diff
+                }
+            }
+        )*
+    };
+}
+
+tuple! {
+    (A 0)
+    (A 0 B 1)
+    (A 0 B 1 C 2)
+    (A 0 B 1 C 2 D 3)
+    (A 0 B 1 C 2 D 3 E 4)
+    (A 0 B 1 C 2 D 3 E 4 F 5)
+}
@@ -0,0 +1,12 @@
+use std::fmt::Debug;
+
+/// Access to the fields of a type one at a time, as key/value pairs.
+///
+/// Implemented by `#[derive(CustomDebug)]`. Every field is passed in the same
+/// representation it has in the Debug output, so attributes like
+/// `#[debug(skip)]`, `#[debug(redact)]` and format strings apply the same way.
+/// This lets a structured logger emit JSON or logfmt that hides exactly what
+/// `{:?}` hides.
+pub trait DebugFields {
+    fn for_each_field(&self, f: &mut dyn FnMut(&'static str, &dyn Debug));
+}
@@ -1,10 +1,18 @@
-extern crate proc_macro;
+// Crates that have the "proc-macro" crate type are only allowed to export
+// procedural macros, so the derive macros live in the derive_debug_impl crate
+// and are re-exported from here alongside the traits and types that the code
+// they generate refers to. Users only need to depend on this one crate.
+pub use derive_debug_impl::{CustomDebug, CustomDisplay, DebugDiff};
 
-use proc_macro::TokenStream;
+mod diff;
+mod fields;
+mod recursion;
 
-#[proc_macro_derive(CustomDebug)]
-pub fn derive(input: TokenStream) -> TokenStream {
-    let _ = input;
+pub use crate::diff::{Change, DebugDiff, Diff};
+pub use crate::fields::DebugFields;
 
-    unimplemented!()
+// Used by the generated code; not public API.
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::recursion::{CycleSafe, Depth, Shared};
 }
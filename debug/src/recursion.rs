@@ -0,0 +1,101 @@
+use std::cell::{Cell, RefCell};
+use std::fmt::{self, Debug};
+use std::rc::Rc;
+use std::sync::Arc;
+
+thread_local! {
+    // Number of #[debug(max_depth = N)] impls currently being formatted.
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+    // Addresses of the #[debug(cycle_safe)] pointers currently being
+    // formatted, outermost first.
+    static VISITED: RefCell<Vec<*const ()>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Held by a generated Debug impl with `#[debug(max_depth = N)]` for as long
+/// as it is formatting, to count how deeply such impls are nested.
+pub struct Depth(());
+
+impl Depth {
+    /// Returns None if `max` impls are already being formatted on this
+    /// thread, in which case the caller prints `..` instead of its fields.
+    pub fn enter(max: usize) -> Option<Self> {
+        DEPTH.with(|depth| {
+            if depth.get() >= max {
+                None
+            } else {
+                depth.set(depth.get() + 1);
+                Some(Depth(()))
+            }
+        })
+    }
+}
+
+impl Drop for Depth {
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+/// Shared pointers that `#[debug(cycle_safe)]` can tell apart by address.
+pub trait Shared {
+    /// The address of the allocation, or None if there is nothing to point
+    /// to, like an `Option` that is None.
+    fn address(&self) -> Option<*const ()>;
+}
+
+impl<T: ?Sized> Shared for Rc<T> {
+    fn address(&self) -> Option<*const ()> {
+        Some(Rc::as_ptr(self) as *const ())
+    }
+}
+
+impl<T: ?Sized> Shared for Arc<T> {
+    fn address(&self) -> Option<*const ()> {
+        Some(Arc::as_ptr(self) as *const ())
+    }
+}
+
+impl<P: Shared> Shared for Option<P> {
+    fn address(&self) -> Option<*const ()> {
+        self.as_ref().and_then(Shared::address)
+    }
+}
+
+/// Prints a `#[debug(cycle_safe)]` field, or `<cycle>` if the allocation it
+/// points to is already being printed further up.
+pub struct CycleSafe<'a, P: ?Sized>(pub &'a P);
+
+impl<'a, P> Debug for CycleSafe<'a, P>
+where
+    P: ?Sized + Shared + Debug,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let address = match self.0.address() {
+            Some(address) => address,
+            None => return Debug::fmt(self.0, formatter),
+        };
+        let cycle = VISITED.with(|visited| {
+            let mut visited = visited.borrow_mut();
+            if visited.contains(&address) {
+                true
+            } else {
+                visited.push(address);
+                false
+            }
+        });
+        if cycle {
+            return formatter.write_str("<cycle>");
+        }
+        let _visit = Visit;
+        Debug::fmt(self.0, formatter)
+    }
+}
+
+// Removes the innermost visited address, even if formatting panics.
+struct Visit;
+
+impl Drop for Visit {
+    fn drop(&mut self) {
+        VISITED.with(|visited| visited.borrow_mut().pop());
+    }
+}
@@ -0,0 +1,59 @@
+// Some fields need code, not just a format string, to be printed usefully. Look
+// for a field attribute #[debug(with = "path")] naming a function with the
+// signature:
+//
+//     fn(&T, &mut fmt::Formatter) -> fmt::Result
+//
+// and call it to format that field. The function is not a Debug impl, so you
+// will need to wrap the field in some value that implements Debug by
+// forwarding to the function in order to pass it to DebugStruct::field.
+//
+// A field formatted this way does not need its type to implement Debug, so it
+// should not contribute to the inferred trait bounds either. In the test case
+// below, Packet<NotDebug> must still implement Debug.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+
+mod fmt_hex {
+    use std::fmt;
+
+    pub fn bytes(bytes: &Vec<u8>, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for byte in bytes {
+            write!(formatter, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+fn type_name<T>(_: &T, formatter: &mut fmt::Formatter) -> fmt::Result {
+    formatter.write_str(std::any::type_name::<T>().rsplit("::").next().unwrap())
+}
+
+#[derive(CustomDebug)]
+pub struct Packet<T> {
+    #[debug(with = "type_name")]
+    kind: T,
+    #[debug(with = "fmt_hex::bytes")]
+    payload: Vec<u8>,
+    len: usize,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct NotDebug;
+
+    assert_debug::<Packet<NotDebug>>();
+
+    let packet = Packet {
+        kind: NotDebug,
+        payload: vec![0xde, 0xad, 0xbe, 0xef],
+        len: 4,
+    };
+
+    let debug = format!("{:?}", packet);
+    let expected = "Packet { kind: NotDebug, payload: deadbeef, len: 4 }";
+
+    assert_eq!(debug, expected);
+}
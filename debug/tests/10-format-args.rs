@@ -0,0 +1,119 @@
+// A format string in a debug attribute can refer to other fields of the struct
+// by name as `{self.field}`, in addition to the `{}` placeholders from test
+// 03-custom-format which refer to the field that the attribute is on.
+//
+// Format_args does not understand `self.field` inside of a format string, so
+// you will need to rewrite those placeholders to named arguments:
+//
+//     format_args!("{} {__self_unit}", self.value, __self_unit = self.unit)
+//
+// Also look for #[debug(alt = "...")], which gives a second format string to
+// use instead when the caller asks for the alternate form `{:#?}`, and for a
+// #[debug("...")] attribute on the struct itself which replaces the entire
+// output with a single format string.
+//
+// On a generic struct, the inferred bounds follow the placeholders, like
+// `T: Display` for `{self.value}` on a field of type T or `T: LowerHex` for
+// `{:x}`. A field that is only printed through its own format string does not
+// need `T: Debug`.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Display};
+
+#[derive(CustomDebug)]
+pub struct Measurement {
+    #[debug = "{} {self.unit}"]
+    value: f64,
+    unit: &'static str,
+    #[debug = "{self.samples} samples"]
+    #[debug(alt = "{self.samples} samples, {self.value} {self.unit} each")]
+    samples: u32,
+}
+
+#[derive(CustomDebug)]
+#[debug("{self.major}.{self.minor}.{self.patch}")]
+#[debug(alt = "Version {{ {self.major}.{self.minor}.{self.patch} }}")]
+pub struct Version {
+    major: u16,
+    minor: u16,
+    patch: u16,
+}
+
+#[derive(CustomDebug)]
+#[debug("#{self.0:02x}{self.1:02x}{self.2:02x}")]
+pub struct Rgb(u8, u8, u8);
+
+#[derive(CustomDebug)]
+pub struct Reading<T, U> {
+    #[debug = "{self.value} {self.unit}"]
+    value: T,
+    unit: U,
+}
+
+#[derive(CustomDebug)]
+#[debug("{self.0}/{self.1:x}")]
+pub struct Pair<A, B>(A, B);
+
+#[derive(CustomDebug)]
+pub struct Register<T> {
+    #[debug = "{:#06x}"]
+    bits: T,
+}
+
+#[derive(Debug)]
+pub struct Celsius;
+
+impl Display for Celsius {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("°C")
+    }
+}
+
+// LowerHex, but not Debug.
+pub struct Hex(u32);
+
+impl fmt::LowerHex for Hex {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.0, formatter)
+    }
+}
+
+fn main() {
+    let m = Measurement {
+        value: 9.5,
+        unit: "m/s",
+        samples: 3,
+    };
+
+    let debug = format!("{:?}", m);
+    let expected = r#"Measurement { value: 9.5 m/s, unit: "m/s", samples: 3 samples }"#;
+    assert_eq!(debug, expected);
+
+    let debug = format!("{:#?}", m);
+    let expected = r#"Measurement {
+    value: 9.5 m/s,
+    unit: "m/s",
+    samples: 3 samples, 9.5 m/s each,
+}"#;
+    assert_eq!(debug, expected);
+
+    let v = Version {
+        major: 1,
+        minor: 42,
+        patch: 0,
+    };
+    assert_eq!(format!("{:?}", v), "1.42.0");
+    assert_eq!(format!("{:#?}", v), "Version { 1.42.0 }");
+
+    assert_eq!(format!("{:?}", Rgb(255, 128, 0)), "#ff8000");
+
+    let reading = Reading {
+        value: 21.5,
+        unit: Celsius,
+    };
+    let expected = "Reading { value: 21.5 °C, unit: Celsius }";
+    assert_eq!(format!("{:?}", reading), expected);
+
+    assert_eq!(format!("{:?}", Pair(Celsius, 255)), "°C/ff");
+    assert_eq!(format!("{:?}", Register { bits: Hex(42) }), "Register { bits: 0x002a }");
+}
@@ -0,0 +1,111 @@
+// Add a second derive macro, CustomDisplay, exported from the same crate. It
+// implements std::fmt::Display from a format string given in an attribute on
+// the struct, or on each variant of an enum. Placeholders in the format string
+// refer to fields by name, or by index in a tuple struct:
+//
+//     #[derive(CustomDisplay)]
+//     #[display("{name} ({bitmask:#x})")]
+//     pub struct Field { ... }
+//
+// A newtype can instead forward to the Display impl of its one field with
+// #[display(transparent)].
+//
+// Bounds are inferred the same way as for CustomDebug, except that only the
+// fields mentioned by the format string contribute and each contributes a
+// bound for the formatting trait that its placeholder uses, such as LowerHex
+// for `{bitmask:#x}`. The #[display(bound = "...")] escape hatch from test
+// 08-escape-hatch replaces inferred bounds.
+
+use derive_debug::{CustomDebug, CustomDisplay};
+use std::fmt::{Debug, Display, LowerHex};
+use std::marker::PhantomData;
+
+#[derive(CustomDebug, CustomDisplay)]
+#[display("{name} ({bitmask:#x})")]
+pub struct Field<T> {
+    name: &'static str,
+    bitmask: T,
+}
+
+#[derive(CustomDisplay)]
+#[display(transparent)]
+pub struct Name(&'static str);
+
+#[derive(CustomDisplay)]
+pub enum Shape<T> {
+    #[display("circle of radius {radius}")]
+    Circle { radius: T },
+    #[display("{0}x{1} rectangle")]
+    Rectangle(T, T),
+    #[display(transparent)]
+    Named(Name),
+    Empty,
+}
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDisplay)]
+#[display("{values:?}")]
+pub struct Values<T: Trait, U> {
+    values: Vec<T::Value>,
+    // Not mentioned by the format string.
+    unused: U,
+    marker: PhantomData<T>,
+}
+
+#[derive(CustomDisplay)]
+#[display("wrapping {inner}")]
+#[display(bound = "T::Value: Debug")]
+pub struct Wrapper<T: Trait> {
+    inner: Values<T, ()>,
+}
+
+fn assert_display<F: Display>() {}
+
+fn main() {
+    struct NotDisplay;
+
+    impl Trait for NotDisplay {
+        type Value = u8;
+    }
+
+    fn assert_hex<T: LowerHex + Debug>()
+    where
+        Field<T>: Display + Debug,
+    {
+    }
+    assert_hex::<u16>();
+    assert_display::<Values<NotDisplay, NotDisplay>>();
+    assert_display::<Wrapper<NotDisplay>>();
+
+    let field = Field {
+        name: "F",
+        bitmask: 0b00011100u8,
+    };
+    assert_eq!(field.to_string(), "F (0x1c)");
+    assert_eq!(format!("{:?}", field), r#"Field { name: "F", bitmask: 28 }"#);
+
+    assert_eq!(Name("n").to_string(), "n");
+    assert_eq!(Shape::Circle { radius: 1 }.to_string(), "circle of radius 1");
+    assert_eq!(Shape::Rectangle(2, 3).to_string(), "2x3 rectangle");
+    assert_eq!(Shape::<u8>::Named(Name("blob")).to_string(), "blob");
+    assert_eq!(Shape::<u8>::Empty.to_string(), "Empty");
+
+    let values = Values::<NotDisplay, NotDisplay> {
+        values: vec![1, 2],
+        unused: NotDisplay,
+        marker: PhantomData,
+    };
+    assert_eq!(values.to_string(), "[1, 2]");
+
+    let wrapper = Wrapper::<NotDisplay> {
+        inner: Values {
+            values: vec![3],
+            unused: (),
+            marker: PhantomData,
+        },
+    };
+    assert_eq!(wrapper.to_string(), "wrapping [3]");
+}
@@ -0,0 +1,78 @@
+// Accept the field-level form of the escape hatch described in the comments of
+// test 08-escape-hatch. A #[debug(bound = "...")] attribute on a field replaces
+// only the bounds that would have been inferred from that field's type, while
+// bounds inferred from the other fields are kept.
+//
+//     impl<T: Trait, U> Debug for Wrapper<T, U>
+//     where
+//         U: Debug,
+//         T::Value: Debug,
+//     {...}
+//
+// An empty string, #[debug(bound = "")], suppresses inference for the field
+// altogether. This is useful when the field's type implements Debug no matter
+// what its type parameters are.
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+
+pub trait Trait {
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub struct Wrapper<T: Trait, U> {
+    #[debug(bound = "T::Value: Debug")]
+    field: Field<T>,
+    normal: U,
+}
+
+#[derive(CustomDebug)]
+pub struct Field<T: Trait> {
+    values: Vec<T::Value>,
+}
+
+// Implements Debug regardless of T.
+pub struct Opaque<T>(T);
+
+impl<T> Debug for Opaque<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("Opaque")
+    }
+}
+
+#[derive(CustomDebug)]
+pub struct Holder<T, U> {
+    #[debug(bound = "")]
+    opaque: Opaque<T>,
+    normal: U,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct Id;
+
+    impl Trait for Id {
+        type Value = u8;
+    }
+
+    struct NotDebug;
+
+    assert_debug::<Wrapper<Id, u8>>();
+    assert_debug::<Holder<NotDebug, u8>>();
+
+    let wrapper = Wrapper::<Id, _> {
+        field: Field { values: vec![1, 2] },
+        normal: "normal",
+    };
+    let debug = format!("{:?}", wrapper);
+    let expected = r#"Wrapper { field: Field { values: [1, 2] }, normal: "normal" }"#;
+    assert_eq!(debug, expected);
+
+    let holder = Holder {
+        opaque: Opaque(NotDebug),
+        normal: 1,
+    };
+    assert_eq!(format!("{:?}", holder), "Holder { opaque: Opaque, normal: 1 }");
+}
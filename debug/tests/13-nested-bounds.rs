@@ -0,0 +1,87 @@
+// Extend the type visitor from tests 05 through 07 so that bound inference
+// works at any depth inside of a field type.
+//
+//   - Associated types can appear nested inside of generic arguments, and in
+//     qualified form like `<T as Trait>::Value`. Either way they get a bound
+//     of their own and do not imply a bound on T.
+//
+//   - Function pointers like `fn(T) -> T`, raw pointers and `dyn Fn(T) -> U`
+//     trait objects do not need their type parameters to implement Debug, so
+//     a parameter mentioned only in those positions does not get a bound.
+//
+//   - PhantomData is one of a small list of "transparent" wrappers that
+//     implement Debug regardless of their type parameter. The caller can add
+//     their own with #[debug(transparent_wrappers = "...")].
+
+use derive_debug::CustomDebug;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::rc::Weak;
+
+pub trait Trait {
+    type Key;
+    type Value;
+}
+
+#[derive(CustomDebug)]
+pub struct Nested<T: Trait> {
+    map: HashMap<T::Key, Vec<<T as Trait>::Value>>,
+}
+
+// Implements Debug regardless of F.
+pub struct Callback<F: ?Sized>(Box<F>);
+
+impl<F: ?Sized> Debug for Callback<F> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("Callback")
+    }
+}
+
+#[derive(CustomDebug)]
+pub struct Functions<T, U> {
+    pointer: fn(T) -> T,
+    callback: Callback<dyn Fn(T) -> U>,
+    parent: Weak<U>,
+    raw: *const T,
+}
+
+pub struct Handle<T>(T);
+
+impl<T> Debug for Handle<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("Handle")
+    }
+}
+
+#[derive(CustomDebug)]
+#[debug(transparent_wrappers = "Handle")]
+pub struct Resource<T, U> {
+    handle: Handle<T>,
+    value: U,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    struct NotDebug;
+
+    impl Trait for NotDebug {
+        type Key = String;
+        type Value = u8;
+    }
+
+    assert_debug::<Nested<NotDebug>>();
+    assert_debug::<Functions<NotDebug, NotDebug>>();
+    assert_debug::<Resource<NotDebug, u8>>();
+
+    let mut map = HashMap::new();
+    map.insert("k".to_owned(), vec![1, 2]);
+    let nested = Nested::<NotDebug> { map };
+    assert_eq!(format!("{:?}", nested), r#"Nested { map: {"k": [1, 2]} }"#);
+
+    let resource = Resource {
+        handle: Handle(NotDebug),
+        value: 1,
+    };
+    assert_eq!(format!("{:?}", resource), "Resource { handle: Handle, value: 1 }");
+}
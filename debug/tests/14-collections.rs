@@ -0,0 +1,68 @@
+// Large collections make for unreadable Debug output. Support three more field
+// attributes:
+//
+//   - #[debug(limit = N)] on any field whose reference is iterable prints the
+//     first N elements as a list, followed by an entry `... (M more)` if some
+//     were left out. When the iterator cannot tell its exact length, as for
+//     an unbounded one, the entry is just `...`, so that printing never walks
+//     the elements past the limit.
+//
+//   - #[debug(hex)] on a byte string like Vec<u8>, [u8; N] or &[u8] prints
+//     the bytes as contiguous hex digits.
+//
+//   - #[debug(ascii)] on a byte string prints it as an escaped b"..."
+//     literal.
+//
+// The byte string attributes can be combined with `limit`. Neither needs the
+// field's type to implement Debug.
+
+use derive_debug::CustomDebug;
+use std::ops::RangeFrom;
+
+// Iterates every natural number.
+pub struct Naturals;
+
+impl<'a> IntoIterator for &'a Naturals {
+    type Item = u64;
+    type IntoIter = RangeFrom<u64>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        0..
+    }
+}
+
+#[derive(CustomDebug)]
+pub struct Buffer<'a> {
+    #[debug(limit = 3)]
+    samples: Vec<u32>,
+    #[debug(limit = 3)]
+    short: [u8; 2],
+    #[debug(hex)]
+    digest: [u8; 4],
+    #[debug(ascii)]
+    request: &'a [u8],
+    #[debug(hex, limit = 2)]
+    payload: Vec<u8>,
+    #[debug(limit = 2)]
+    naturals: Naturals,
+}
+
+fn main() {
+    let buffer = Buffer {
+        samples: (0..10_000).collect(),
+        short: [1, 2],
+        digest: [0xde, 0xad, 0xbe, 0xef],
+        request: b"GET / HTTP/1.1\r\n",
+        payload: vec![0xca, 0xfe, 0xba, 0xbe],
+        naturals: Naturals,
+    };
+
+    let debug = format!("{:?}", buffer);
+    let expected = concat!(
+        r#"Buffer { samples: [0, 1, 2, ... (9997 more)], short: [1, 2], "#,
+        r#"digest: deadbeef, request: b"GET / HTTP/1.1\r\n", "#,
+        r#"payload: cafe ... (2 more), naturals: [0, 1, ...] }"#,
+    );
+
+    assert_eq!(debug, expected);
+}
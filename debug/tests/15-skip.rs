@@ -0,0 +1,88 @@
+// Large config structs are easier to read in Debug output when uninteresting
+// fields are left out. Support:
+//
+//   - #[debug(skip)] on a field, which never prints it;
+//
+//   - #[debug(skip_if = "path")] on a field, which leaves it out whenever the
+//     function `fn(&T) -> bool` returns true, like `Option::is_none`;
+//
+//   - #[debug(skip_defaults)] on the struct, which leaves out every field that
+//     is equal to Default::default(). This requires the field types to
+//     implement Default and PartialEq, so those traits are added to the
+//     inferred bounds of any type parameter the fields mention.
+//
+// Whenever a field has been left out, finish the output with
+// DebugStruct::finish_non_exhaustive so that the result ends with `..` and
+// reads as valid Debug output of a struct with hidden fields.
+
+use derive_debug::CustomDebug;
+use std::fmt::Debug;
+
+#[derive(CustomDebug)]
+pub struct Request {
+    url: &'static str,
+    #[debug(skip_if = "Option::is_none")]
+    timeout: Option<u32>,
+    #[debug(skip_if = "Vec::is_empty")]
+    headers: Vec<(&'static str, &'static str)>,
+}
+
+#[derive(CustomDebug)]
+pub struct Credentials {
+    user: &'static str,
+    #[debug(skip)]
+    password: &'static str,
+}
+
+#[derive(CustomDebug, Default)]
+#[debug(skip_defaults)]
+pub struct Config<T> {
+    name: String,
+    retries: u32,
+    verbose: bool,
+    extra: Option<T>,
+}
+
+fn assert_debug<F: Debug>() {}
+
+fn main() {
+    assert_debug::<Config<u8>>();
+
+    let request = Request {
+        url: "/",
+        timeout: None,
+        headers: Vec::new(),
+    };
+    assert_eq!(format!("{:?}", request), r#"Request { url: "/", .. }"#);
+
+    let request = Request {
+        url: "/",
+        timeout: Some(30),
+        headers: vec![("Accept", "*/*")],
+    };
+    let expected = r#"Request { url: "/", timeout: Some(30), headers: [("Accept", "*/*")] }"#;
+    assert_eq!(format!("{:?}", request), expected);
+
+    let credentials = Credentials {
+        user: "root",
+        password: "hunter2",
+    };
+    assert_eq!(format!("{:?}", credentials), r#"Credentials { user: "root", .. }"#);
+
+    let config = Config::<u8> {
+        name: "server".to_owned(),
+        retries: 3,
+        ..Config::default()
+    };
+    let expected = r#"Config { name: "server", retries: 3, .. }"#;
+    assert_eq!(format!("{:?}", config), expected);
+
+    let config = Config {
+        name: "server".to_owned(),
+        retries: 1,
+        verbose: true,
+        extra: Some(0u8),
+    };
+    let expected = r#"Config { name: "server", retries: 1, verbose: true, extra: Some(0) }"#;
+    assert_eq!(format!("{:?}", config), expected);
+}
@@ -0,0 +1,105 @@
+// Add a third derive macro, DebugDiff, which implements the derive_debug::DebugDiff
+// trait:
+//
+//     pub trait DebugDiff: Debug {
+//         fn debug_diff(&self, other: &Self) -> Diff;
+//     }
+//
+// The resulting Diff lists only the fields that differ between two values,
+// each by its path. Fields whose type implements DebugDiff are compared
+// recursively, so that nested structs, vectors and maps report the innermost
+// part that changed, like `args[1]: "build" -> "test"`.
+//
+// Because proc-macro crates can only export macros, the trait and the Diff
+// type need to live in a regular library crate which re-exports the derive
+// macros, the same arrangement as the bitfield project uses.
+//
+// The derive shares the #[debug(...)] attributes of CustomDebug. Skipped
+// fields are not compared, and fields with a custom format string or `with`
+// function are compared by their formatted representation. Bounds are inferred
+// the same way as for CustomDebug but with DebugDiff in place of Debug.
+
+use derive_debug::{CustomDebug, DebugDiff};
+use std::collections::BTreeMap;
+
+#[derive(CustomDebug, DebugDiff)]
+pub struct Command {
+    program: String,
+    args: Vec<&'static str>,
+    env: BTreeMap<&'static str, String>,
+    options: Options,
+    #[debug(skip)]
+    pid: u32,
+    #[debug = "0b{:04b}"]
+    flags: u8,
+}
+
+#[derive(CustomDebug, DebugDiff)]
+pub struct Options {
+    verbose: bool,
+    jobs: Option<u32>,
+    mode: Mode,
+}
+
+#[derive(CustomDebug, DebugDiff)]
+pub enum Mode {
+    Fast,
+    Slow { delay: u32 },
+}
+
+#[derive(CustomDebug, DebugDiff)]
+pub struct Pair<T>(T, T);
+
+fn assert_debug_diff<T: DebugDiff>() {}
+
+fn main() {
+    assert_debug_diff::<Pair<Pair<u8>>>();
+
+    let mut env = BTreeMap::new();
+    env.insert("PATH", "/bin".to_owned());
+    let before = Command {
+        program: "cargo".to_owned(),
+        args: vec!["+nightly", "build"],
+        env: env.clone(),
+        options: Options {
+            verbose: false,
+            jobs: Some(4),
+            mode: Mode::Slow { delay: 1 },
+        },
+        pid: 1,
+        flags: 0b0001,
+    };
+
+    env.insert("RUST_LOG", "debug".to_owned());
+    let after = Command {
+        program: "cargo".to_owned(),
+        args: vec!["+nightly", "test", "--all"],
+        env,
+        options: Options {
+            verbose: false,
+            jobs: None,
+            mode: Mode::Slow { delay: 2 },
+        },
+        pid: 2,
+        flags: 0b0011,
+    };
+
+    let diff = before.debug_diff(&after);
+    let expected = r#"args[1]: "build" -> "test"
+args[2]: <absent> -> "--all"
+env["RUST_LOG"]: <absent> -> "debug"
+options.jobs: Some(4) -> None
+options.mode.delay: 1 -> 2
+flags: 0b0001 -> 0b0011"#;
+    assert_eq!(diff.to_string(), expected);
+    assert_eq!(diff.len(), 6);
+    assert_eq!(diff.changes()[0].path(), "args[1]");
+
+    assert!(before.debug_diff(&before).is_empty());
+
+    let diff = Mode::Fast.debug_diff(&Mode::Slow { delay: 1 });
+    assert_eq!(diff.to_string(), "Fast -> Slow { delay: 1 }");
+
+    let diff = Pair(Pair(1, 2), Pair(3, 4)).debug_diff(&Pair(Pair(1, 2), Pair(3, 5)));
+    assert_eq!(diff.to_string(), "1.1: 4 -> 5");
+}
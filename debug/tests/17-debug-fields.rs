@@ -0,0 +1,79 @@
+// Structured loggers want key/value pairs rather than one Debug string. Along
+// with Debug, generate an impl of derive_debug::DebugFields whose
+// for_each_field method passes each field's name and value to a callback.
+//
+// The values must be exactly what the Debug output would show: fields with
+// #[debug(skip)] or a skip_if condition that holds are left out, format
+// strings apply, and the new #[debug(redact)] attribute replaces the value
+// with `[REDACTED]` in both places. That way a logging backend emitting JSON
+// or logfmt follows the same redaction rules as `{:?}`.
+
+use derive_debug::{CustomDebug, DebugFields};
+
+#[derive(CustomDebug)]
+pub struct Login {
+    user: String,
+    #[debug(redact)]
+    password: String,
+    #[debug = "0x{:x}"]
+    flags: u8,
+    #[debug(skip)]
+    attempts: u32,
+    #[debug(skip_if = "Option::is_none")]
+    otp: Option<u32>,
+}
+
+#[derive(CustomDebug)]
+pub enum Event {
+    Connect { host: &'static str, port: u16 },
+    Disconnect(#[debug(redact)] String),
+}
+
+fn pairs(value: &dyn DebugFields) -> Vec<(&'static str, String)> {
+    let mut pairs = Vec::new();
+    value.for_each_field(&mut |name, value| pairs.push((name, format!("{:?}", value))));
+    pairs
+}
+
+fn main() {
+    let login = Login {
+        user: "alice".to_owned(),
+        password: "hunter2".to_owned(),
+        flags: 42,
+        attempts: 3,
+        otp: None,
+    };
+
+    let debug = format!("{:?}", login);
+    assert_eq!(
+        debug,
+        r#"Login { user: "alice", password: [REDACTED], flags: 0x2a, .. }"#,
+    );
+    assert_eq!(
+        pairs(&login),
+        [
+            ("user", r#""alice""#.to_owned()),
+            ("password", "[REDACTED]".to_owned()),
+            ("flags", "0x2a".to_owned()),
+        ],
+    );
+
+    let login = Login {
+        otp: Some(123456),
+        ..login
+    };
+    assert_eq!(pairs(&login).last().unwrap(), &("otp", "Some(123456)".to_owned()));
+
+    let connect = Event::Connect {
+        host: "localhost",
+        port: 8080,
+    };
+    assert_eq!(
+        pairs(&connect),
+        [("host", r#""localhost""#.to_owned()), ("port", "8080".to_owned())],
+    );
+
+    let disconnect = Event::Disconnect("token".to_owned());
+    assert_eq!(format!("{:?}", disconnect), "Disconnect([REDACTED])");
+    assert_eq!(pairs(&disconnect), [("0", "[REDACTED]".to_owned())]);
+}
@@ -0,0 +1,95 @@
+// The One<T>/Two<T> types from test 06 are recursive at the type level, but
+// values can be too: printing a deep tree takes a lot of output, and printing
+// an Rc<RefCell<Node>> graph that contains a cycle never finishes. Support:
+//
+//   - #[debug(max_depth = N)] on the type, which prints `..` in place of the
+//     value once N values of types with this attribute are nested inside one
+//     another. The depth is tracked in a thread-local by the generated impls.
+//
+//   - #[debug(cycle_safe)] on an Rc or Arc field, or an Option of one, which
+//     remembers the addresses of the allocations currently being printed and
+//     prints `<cycle>` when it comes back around to one of them.
+//
+// The runtime support lives in a module of the derive_debug crate that the
+// generated code refers to.
+
+use derive_debug::CustomDebug;
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[derive(CustomDebug)]
+#[debug(max_depth = 2)]
+pub struct Tree {
+    value: u32,
+    children: Vec<Tree>,
+}
+
+#[derive(CustomDebug)]
+pub struct Node {
+    name: &'static str,
+    #[debug(cycle_safe)]
+    next: Option<Rc<RefCell<Node>>>,
+}
+
+#[derive(CustomDebug)]
+pub struct Shared {
+    #[debug(cycle_safe)]
+    left: Arc<u8>,
+    #[debug(cycle_safe)]
+    right: Arc<u8>,
+}
+
+fn leaf(value: u32) -> Tree {
+    Tree {
+        value,
+        children: Vec::new(),
+    }
+}
+
+fn main() {
+    let tree = Tree {
+        value: 1,
+        children: vec![Tree {
+            value: 2,
+            children: vec![leaf(3)],
+        }],
+    };
+    assert_eq!(
+        format!("{:?}", tree),
+        "Tree { value: 1, children: [Tree { value: 2, children: [..] }] }",
+    );
+    assert_eq!(format!("{:?}", leaf(4)), "Tree { value: 4, children: [] }");
+
+    let a = Rc::new(RefCell::new(Node {
+        name: "a",
+        next: None,
+    }));
+    let b = Rc::new(RefCell::new(Node {
+        name: "b",
+        next: Some(a.clone()),
+    }));
+    let start = Node {
+        name: "start",
+        next: Some(b.clone()),
+    };
+    assert_eq!(
+        format!("{:?}", start),
+        r#"Node { name: "start", next: Some(RefCell { value: Node { name: "b", next: Some(RefCell { value: Node { name: "a", next: None } }) } }) }"#,
+    );
+
+    a.borrow_mut().next = Some(b.clone());
+    assert_eq!(
+        format!("{:?}", start),
+        r#"Node { name: "start", next: Some(RefCell { value: Node { name: "b", next: Some(RefCell { value: Node { name: "a", next: <cycle> } }) } }) }"#,
+    );
+    a.borrow_mut().next = None;
+
+    // The same allocation reachable twice without a cycle prints both times.
+    let byte = Arc::new(7);
+    let shared = Shared {
+        left: byte.clone(),
+        right: byte,
+    };
+    assert_eq!(format!("{:?}", shared), "Shared { left: 7, right: 7 }");
+}
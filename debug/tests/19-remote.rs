@@ -0,0 +1,147 @@
+// The Debug impls of types from other crates can't be changed, yet those are
+// often the ones that need it, like a third-party config struct holding a
+// password. Following the "remote" pattern from serde, support
+// #[debug(remote = "path::to::Type")] on a local mirror of the other type.
+//
+// Instead of implementing Debug for the mirror, generate an associated
+// function:
+//
+//     impl ConfigDef {
+//         fn fmt_remote(value: &other::Config, formatter: &mut Formatter) -> fmt::Result {
+//             ...
+//         }
+//     }
+//
+// which prints the remote value according to the mirror's attributes. It has
+// the right signature for #[debug(with = "ConfigDef::fmt_remote")] on a field
+// of the remote type.
+//
+// A mirror may leave out fields of the remote type, but then the output has to
+// say so. Such a mirror needs #[debug(non_exhaustive)], which ends the output
+// with `..` like it does on a local type. On an enum the attribute goes on the
+// variants that leave out fields.
+//
+// The generated function also refers to every field of the remote type with
+// the type that the mirror declares, so a misspelled field name, a wrong type
+// or a left out field without #[debug(non_exhaustive)] is a compile error
+// pointing at the mirror.
+//
+// The mirror is never constructed, yet its fields and variants must not be
+// reported as dead code, which would fail builds with warnings denied. Tests
+// allow dead code unless it is denied explicitly.
+
+#![deny(warnings, dead_code)]
+
+use derive_debug::CustomDebug;
+use std::fmt::{self, Debug};
+
+mod other {
+    pub struct Config {
+        pub user: String,
+        pub password: String,
+        pub retries: u32,
+    }
+
+    pub struct Point {
+        pub x: i32,
+        pub y: i32,
+    }
+
+    pub enum Auth<T> {
+        Anonymous,
+        Token(T),
+        Basic { user: String, password: String },
+    }
+}
+
+#[derive(CustomDebug)]
+#[debug(remote = "other::Config", non_exhaustive)]
+struct ConfigDef {
+    user: String,
+    #[debug(redact)]
+    password: String,
+}
+
+#[derive(CustomDebug)]
+#[debug(remote = "other::Point")]
+struct PointDef {
+    x: i32,
+    y: i32,
+}
+
+#[derive(CustomDebug)]
+#[debug(remote = "other::Auth<T>")]
+enum AuthDef<T> {
+    Anonymous,
+    Token(#[debug(redact)] T),
+    Basic {
+        user: String,
+        #[debug(skip)]
+        password: String,
+    },
+}
+
+#[derive(CustomDebug)]
+pub struct Client {
+    #[debug(with = "ConfigDef::fmt_remote")]
+    config: other::Config,
+    #[debug(with = "AuthDef::fmt_remote")]
+    auth: other::Auth<u64>,
+}
+
+struct Remote<'a>(&'a other::Config);
+
+impl<'a> Debug for Remote<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        ConfigDef::fmt_remote(self.0, formatter)
+    }
+}
+
+struct RemotePoint(other::Point);
+
+impl Debug for RemotePoint {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        PointDef::fmt_remote(&self.0, formatter)
+    }
+}
+
+fn main() {
+    let config = other::Config {
+        user: "admin".to_owned(),
+        password: "hunter2".to_owned(),
+        retries: 3,
+    };
+    assert_eq!(config.retries, 3);
+    assert_eq!(
+        format!("{:?}", Remote(&config)),
+        r#"Config { user: "admin", password: [REDACTED], .. }"#,
+    );
+    assert_eq!(
+        format!("{:?}", RemotePoint(other::Point { x: 1, y: -2 })),
+        "Point { x: 1, y: -2 }",
+    );
+
+    let client = Client {
+        config,
+        auth: other::Auth::Token(12345),
+    };
+    assert_eq!(
+        format!("{:?}", client),
+        r#"Client { config: Config { user: "admin", password: [REDACTED], .. }, auth: Token([REDACTED]) }"#,
+    );
+
+    let client = Client {
+        auth: other::Auth::Basic {
+            user: "admin".to_owned(),
+            password: "hunter2".to_owned(),
+        },
+        ..client
+    };
+    assert!(format!("{:?}", client).ends_with(r#"auth: Basic { user: "admin", .. } }"#));
+
+    let client = Client {
+        auth: other::Auth::Anonymous,
+        ..client
+    };
+    assert!(format!("{:?}", client).ends_with("auth: Anonymous }"));
+}
@@ -0,0 +1,32 @@
+// The mirror of a remote type has to agree with the remote type on the names
+// and types of the fields it lists. A mirror that leaves out fields has to be
+// marked #[debug(non_exhaustive)], or else its output would look complete.
+
+use derive_debug::CustomDebug;
+
+mod other {
+    pub struct Config {
+        pub user: String,
+        pub retries: u32,
+    }
+
+    pub struct Limits {
+        pub memory: u64,
+        pub files: u32,
+    }
+}
+
+#[derive(CustomDebug)]
+#[debug(remote = "other::Config")]
+struct ConfigDef {
+    user: String,
+    retries: u64,
+}
+
+#[derive(CustomDebug)]
+#[debug(remote = "other::Limits")]
+struct LimitsDef {
+    memory: u64,
+}
+
+fn main() {}
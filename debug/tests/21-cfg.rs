@@ -0,0 +1,100 @@
+// Fields can be compiled in or out with #[cfg(...)], for example behind a
+// cargo feature. The compiler evaluates #[cfg] and #[cfg_attr] on the input of
+// a derive macro before invoking it, so the macro only ever sees the fields
+// that are enabled, with the remaining tuple fields already renumbered. This
+// test makes sure nothing in the generated code refers to a field that has
+// been configured out, including the DebugFields and DebugDiff impls.
+//
+// It uses `cfg(all())`, which is always enabled, and `cfg(any())`, which never
+// is, to cover both states of a cfg in a single build.
+//
+// Separately, types marked #[non_exhaustive] may want their Debug output to
+// say that there might be more to them. With #[debug(non_exhaustive)] on a
+// struct or on an enum variant, always finish with `..` by calling
+// DebugStruct::finish_non_exhaustive.
+
+use derive_debug::{CustomDebug, DebugDiff, DebugFields};
+
+#[derive(CustomDebug, DebugDiff)]
+pub struct Config {
+    name: &'static str,
+    #[cfg(all())]
+    enabled: bool,
+    #[cfg(any())]
+    disabled: NotDebug,
+}
+
+pub struct NotDebug;
+
+#[derive(CustomDebug)]
+pub enum Event {
+    Start {
+        #[cfg(any())]
+        id: NotDebug,
+        #[cfg(all())]
+        at: u32,
+    },
+    Stop(u32, #[cfg(any())] NotDebug),
+    Fail(u32, #[cfg(all())] &'static str),
+}
+
+#[derive(CustomDebug)]
+pub struct Pair(#[cfg(any())] NotDebug, u16, #[cfg(all())] u8);
+
+#[derive(CustomDebug)]
+#[debug(non_exhaustive)]
+#[non_exhaustive]
+pub struct Options {
+    pub verbose: bool,
+}
+
+#[derive(CustomDebug)]
+#[debug(non_exhaustive)]
+#[non_exhaustive]
+pub struct Marker;
+
+#[derive(CustomDebug)]
+#[non_exhaustive]
+pub enum Error {
+    #[debug(non_exhaustive)]
+    #[non_exhaustive]
+    Io { code: i32 },
+    #[non_exhaustive]
+    Other(u8),
+}
+
+fn main() {
+    let config = Config {
+        name: "app",
+        enabled: true,
+    };
+    assert_eq!(
+        format!("{:?}", config),
+        r#"Config { name: "app", enabled: true }"#,
+    );
+
+    let mut names = Vec::new();
+    config.for_each_field(&mut |name, _value| names.push(name));
+    assert_eq!(names, ["name", "enabled"]);
+
+    let other = Config {
+        name: "app",
+        enabled: false,
+    };
+    assert_eq!(
+        derive_debug::DebugDiff::debug_diff(&config, &other).to_string(),
+        "enabled: true -> false",
+    );
+
+    assert_eq!(format!("{:?}", Event::Start { at: 1 }), "Start { at: 1 }");
+    assert_eq!(format!("{:?}", Event::Stop(2)), "Stop(2)");
+    assert_eq!(format!("{:?}", Event::Fail(3, "oops")), r#"Fail(3, "oops")"#);
+
+    assert_eq!(format!("{:?}", Pair(1, 2)), "Pair(1, 2)");
+
+    let options = Options { verbose: true };
+    assert_eq!(format!("{:?}", options), "Options { verbose: true, .. }");
+    assert_eq!(format!("{:?}", Marker), "Marker { .. }");
+    assert_eq!(format!("{:?}", Error::Io { code: 2 }), "Io { code: 2, .. }");
+    assert_eq!(format!("{:?}", Error::Other(1)), "Other(1)");
+}
@@ -0,0 +1,26 @@
+// The string in #[debug(bound = "...")] has to parse as a comma-separated list
+// of where-predicates, and the error points into the string literal when it
+// does not. The attribute is supported on the container and on fields only;
+// on an enum variant it is rejected at the attribute.
+
+use derive_debug::CustomDebug;
+
+#[derive(CustomDebug)]
+pub struct Field<T> {
+    #[debug(bound = "T Debug")]
+    value: T,
+}
+
+#[derive(CustomDebug)]
+#[debug(bound = "impl Debug for T")]
+pub struct Container<T> {
+    value: T,
+}
+
+#[derive(CustomDebug)]
+pub enum Variant<T> {
+    #[debug(bound = "T: Debug")]
+    Value(T),
+}
+
+fn main() {}
@@ -1,12 +1,26 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-parse.rs");
-    //t.pass("tests/02-impl-debug.rs");
-    //t.pass("tests/03-custom-format.rs");
-    //t.pass("tests/04-type-parameter.rs");
-    //t.pass("tests/05-phantom-data.rs");
-    //t.pass("tests/06-bound-trouble.rs");
-    //t.pass("tests/07-associated-type.rs");
-    //t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/01-parse.rs");
+    t.pass("tests/02-impl-debug.rs");
+    t.pass("tests/03-custom-format.rs");
+    t.pass("tests/04-type-parameter.rs");
+    t.pass("tests/05-phantom-data.rs");
+    t.pass("tests/06-bound-trouble.rs");
+    t.pass("tests/07-associated-type.rs");
+    t.pass("tests/08-escape-hatch.rs");
+    t.pass("tests/09-custom-formatter.rs");
+    t.pass("tests/10-format-args.rs");
+    t.pass("tests/11-display.rs");
+    t.pass("tests/12-field-bound.rs");
+    t.pass("tests/13-nested-bounds.rs");
+    t.pass("tests/14-collections.rs");
+    t.pass("tests/15-skip.rs");
+    t.pass("tests/16-debug-diff.rs");
+    t.pass("tests/17-debug-fields.rs");
+    t.pass("tests/18-recursion.rs");
+    t.pass("tests/19-remote.rs");
+    t.compile_fail("tests/20-remote-mismatch.rs");
+    t.pass("tests/21-cfg.rs");
+    t.compile_fail("tests/22-bound-errors.rs");
 }
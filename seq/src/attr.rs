@@ -0,0 +1,173 @@
+use crate::expand;
+use crate::parse::{Header, Seq};
+use proc_macro2::{Delimiter, Group, Ident, Punct, Spacing, TokenStream, TokenTree};
+use syn::ext::IdentExt;
+use syn::parse::Parser;
+use syn::{Arm, Error, Field, Result, Variant};
+
+// #[seq::repeat(N in 0..8)] on an item repeats the whole item, unless some
+// element inside it is marked with #[seq::each], in which case the item is
+// emitted once and only the marked elements repeat. Either way the item is
+// the body of an equivalent seq! call, with every marked element turned into
+// a #(...)* section.
+//
+// The item has to parse as Rust before the attribute gets to see it, which
+// rules out `Irq#N`. Instead a loop variable is pasted into an identifier by
+// writing it between double underscores, as in `Irq__N__`.
+pub fn repeat(header: Header, item: TokenStream) -> Result<TokenStream> {
+    let names = header
+        .vars
+        .iter()
+        .flat_map(|var| &var.idents)
+        .map(Ident::to_string)
+        .collect::<Vec<_>>();
+    let body = mark_sections(item, &names)?;
+    let seq = Seq { header, body };
+    expand::expand(&seq)
+}
+
+fn mark_sections(tokens: TokenStream, names: &[String]) -> Result<TokenStream> {
+    let tokens = tokens.into_iter().collect::<Vec<_>>();
+    let mut output = TokenStream::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        if is_each(&tokens[i..]) {
+            let start = i + 2;
+            let len = element(&tokens[start..]);
+            if len == 0 {
+                let msg = "expected an enum variant, field or match arm after `#[seq::each]`";
+                return Err(Error::new(tokens[i + 1].span(), msg));
+            }
+            let element = tokens[start..start + len].iter().cloned().collect();
+            let mut group = Group::new(Delimiter::Parenthesis, mark_sections(element, names)?);
+            group.set_span(tokens[i + 1].span());
+            let mut pound = Punct::new('#', Spacing::Alone);
+            pound.set_span(tokens[i].span());
+            let mut star = Punct::new('*', Spacing::Alone);
+            star.set_span(tokens[i + 1].span());
+            output.extend([
+                TokenTree::Punct(pound),
+                TokenTree::Group(group),
+                TokenTree::Punct(star),
+            ]);
+            i = start + len;
+            continue;
+        }
+        match &tokens[i] {
+            TokenTree::Ident(ident) => output.extend(placeholders(ident, names)?),
+            TokenTree::Group(group) => {
+                let stream = mark_sections(group.stream(), names)?;
+                let mut marked = Group::new(group.delimiter(), stream);
+                marked.set_span(group.span());
+                output.extend([TokenTree::Group(marked)]);
+            }
+            token => output.extend([token.clone()]),
+        }
+        i += 1;
+    }
+    Ok(output)
+}
+
+// Turns `read__T__bytes` into `read # T # bytes` for the loop variable `T`.
+fn placeholders(ident: &Ident, names: &[String]) -> Result<TokenStream> {
+    let text = ident.to_string();
+    let mut pieces = Vec::new();
+    let mut rest = text.as_str();
+    loop {
+        let next = names
+            .iter()
+            .filter_map(|name| {
+                let placeholder = format!("__{}__", name);
+                rest.find(&placeholder)
+                    .map(|i| (i, name, placeholder.len()))
+            })
+            .min();
+        let (i, name, len) = match next {
+            Some(next) => next,
+            None => break,
+        };
+        if i > 0 {
+            pieces.push(&rest[..i]);
+        }
+        pieces.push(name);
+        rest = &rest[i + len..];
+    }
+    if pieces.is_empty() {
+        return Ok(TokenTree::Ident(ident.clone()).into());
+    }
+    if !rest.is_empty() {
+        pieces.push(rest);
+    }
+    let mut output = TokenStream::new();
+    for (i, piece) in pieces.into_iter().enumerate() {
+        if i > 0 {
+            let mut pound = Punct::new('#', Spacing::Alone);
+            pound.set_span(ident.span());
+            output.extend([TokenTree::Punct(pound)]);
+        }
+        let mut piece = match Ident::parse_any.parse_str(piece) {
+            Ok(piece) => piece,
+            Err(_) => {
+                let msg = format!("cannot paste `{}` in `{}`", piece, text);
+                return Err(Error::new(ident.span(), msg));
+            }
+        };
+        piece.set_span(ident.span());
+        output.extend([TokenTree::Ident(piece)]);
+    }
+    Ok(output)
+}
+
+// Recognizes `#[seq::each]` at the start of `tokens`.
+fn is_each(tokens: &[TokenTree]) -> bool {
+    match tokens {
+        [TokenTree::Punct(pound), TokenTree::Group(group), ..]
+            if pound.as_char() == '#' && group.delimiter() == Delimiter::Bracket =>
+        {
+            let path = group.stream().to_string().replace(' ', "");
+            path == "seq::each" || path == "::seq::each"
+        }
+        _ => false,
+    }
+}
+
+// The number of tokens in the element at the start of `tokens`, which is
+// everything up to and including the first comma at this level that follows
+// a whole field, variant or match arm. The commas in between belong to things
+// like `HashMap<u8, u16>` or `f::<A, B>()`, which are not in any group. If
+// no comma follows a whole element, it ends at the first comma, and the
+// compiler reports what is wrong with it. Like in Rust itself, a match arm
+// whose body is a block ends after the block, with or without a comma.
+fn element(tokens: &[TokenTree]) -> usize {
+    let is_punct = |i: usize, ch: char| match tokens.get(i) {
+        Some(TokenTree::Punct(punct)) => punct.as_char() == ch,
+        _ => false,
+    };
+    let mut first_comma = None;
+    let mut i = 0;
+    while i < tokens.len() {
+        if is_punct(i, ',') {
+            if is_element(&tokens[..i]) {
+                return i + 1;
+            }
+            first_comma = first_comma.or(Some(i + 1));
+        }
+        if is_punct(i, '=') && is_punct(i + 1, '>') {
+            if let Some(TokenTree::Group(group)) = tokens.get(i + 2) {
+                if group.delimiter() == Delimiter::Brace {
+                    return i + 3 + is_punct(i + 3, ',') as usize;
+                }
+            }
+        }
+        i += 1;
+    }
+    first_comma.unwrap_or(tokens.len())
+}
+
+fn is_element(tokens: &[TokenTree]) -> bool {
+    let tokens = tokens.iter().cloned().collect::<TokenStream>();
+    Field::parse_named.parse2(tokens.clone()).is_ok()
+        || Field::parse_unnamed.parse2(tokens.clone()).is_ok()
+        || syn::parse2::<Variant>(tokens.clone()).is_ok()
+        || syn::parse2::<Arm>(tokens).is_ok()
+}
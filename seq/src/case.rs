@@ -0,0 +1,103 @@
+use proc_macro2::{Spacing, TokenTree};
+
+// A case modifier after a loop variable, as in `read_#T:snake`.
+#[derive(Copy, Clone)]
+pub enum Case {
+    // `read_u16`
+    Snake,
+    // `readU16`
+    Camel,
+    // `ReadU16`
+    Pascal,
+    // `READ_U16`
+    Upper,
+}
+
+impl Case {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "snake" => Some(Case::Snake),
+            "camel" => Some(Case::Camel),
+            "pascal" => Some(Case::Pascal),
+            "upper" => Some(Case::Upper),
+            _ => None,
+        }
+    }
+
+    // Recognizes `: snake` at the start of `tokens`.
+    pub fn parse(tokens: &[TokenTree]) -> Option<Self> {
+        match tokens {
+            [TokenTree::Punct(colon), TokenTree::Ident(name), ..]
+                if colon.as_char() == ':' && colon.spacing() == Spacing::Alone =>
+            {
+                Case::from_name(&name.to_string())
+            }
+            _ => None,
+        }
+    }
+
+    pub fn apply(self, text: &str) -> String {
+        let words = words(text);
+        match self {
+            Case::Snake => words.join("_").to_lowercase(),
+            Case::Upper => words.join("_").to_uppercase(),
+            Case::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, word)| match i {
+                    0 => word.to_lowercase(),
+                    _ => capitalize(word),
+                })
+                .collect(),
+            Case::Pascal => words.iter().map(|word| capitalize(word)).collect(),
+        }
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    let first = chars.next().map(|ch| ch.to_ascii_uppercase());
+    first
+        .into_iter()
+        .chain(chars.flat_map(char::to_lowercase))
+        .collect()
+}
+
+// Splits an identifier into words at underscores and at changes of case, so
+// that `HTTPRequest`, `http_request` and `HttpRequest` all consist of `http`
+// and `request` in some case. Digits belong to the word before them, so `U16`
+// is a single word.
+fn words(text: &str) -> Vec<&str> {
+    let chars = text.char_indices().collect::<Vec<_>>();
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, &(offset, ch)) in chars.iter().enumerate() {
+        if ch == '_' {
+            if let Some(start) = start.take() {
+                words.push(&text[start..offset]);
+            }
+            continue;
+        }
+        let boundary = ch.is_uppercase()
+            && i > 0
+            && match chars[i - 1].1 {
+                prev if prev.is_lowercase() || prev.is_numeric() => true,
+                prev if prev.is_uppercase() => chars
+                    .get(i + 1)
+                    .is_some_and(|&(_, next)| next.is_lowercase()),
+                _ => false,
+            };
+        match start {
+            Some(begin) if boundary => {
+                words.push(&text[begin..offset]);
+                start = Some(offset);
+            }
+            Some(_) => {}
+            None => start = Some(offset),
+        }
+    }
+    if let Some(start) = start {
+        words.push(&text[start..]);
+    }
+    words
+}
@@ -0,0 +1,91 @@
+use crate::parse::{Value, Var};
+use proc_macro2::Ident;
+
+// The loop variables that are bound at the current point of the body.
+pub struct Env<'a> {
+    bindings: Vec<(&'a Ident, &'a Value)>,
+    // The loops being iterated, innermost last.
+    loops: Vec<&'a Var>,
+    // Iterations so far and how many there may be.
+    iterations: usize,
+    limit: usize,
+}
+
+impl<'a> Env<'a> {
+    pub fn new(limit: usize) -> Self {
+        Env {
+            bindings: Vec::new(),
+            loops: Vec::new(),
+            iterations: 0,
+            limit,
+        }
+    }
+
+    // Adds the iterations of a loop that is about to run to the total, and
+    // returns false if that exceeds the limit.
+    pub fn count(&mut self, iterations: usize) -> bool {
+        self.iterations = self.iterations.saturating_add(iterations);
+        self.iterations <= self.limit
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    pub fn enter(&mut self, var: &'a Var) {
+        self.loops.push(var);
+    }
+
+    pub fn exit(&mut self) {
+        self.loops.pop();
+    }
+
+    pub fn bind(&mut self, var: &'a Var, values: &'a [Value]) {
+        self.bindings.extend(var.idents.iter().zip(values));
+    }
+
+    pub fn unbind(&mut self, var: &Var) {
+        let len = self.bindings.len() - var.idents.len();
+        self.bindings.truncate(len);
+    }
+
+    pub fn lookup(&self, ident: &Ident) -> Option<&'a Value> {
+        self.bindings
+            .iter()
+            .rev()
+            .find(|(var, _value)| *var == ident)
+            .map(|(_var, value)| *value)
+    }
+
+    // The type of the literals that `ident` turns into, for a variable of a
+    // loop like `N: u8 in 0..16` and for `FIRST` and `LAST` of such a loop.
+    pub fn ty(&self, ident: &Ident) -> Option<&'a Ident> {
+        let var = if self.lookup(ident).is_some() {
+            self.loops
+                .iter()
+                .rev()
+                .find(|var| var.idents.contains(ident))?
+        } else if ident == "FIRST" || ident == "LAST" {
+            self.loops.last()?
+        } else {
+            return None;
+        };
+        var.ty.as_ref()
+    }
+
+    // A loop variable, or else one of the built-in variables `FIRST` and
+    // `LAST`, the first and last value of the innermost loop variable, and
+    // `COUNT`, the number of iterations of the innermost loop.
+    pub fn resolve(&self, ident: &Ident) -> Option<Value> {
+        if let Some(value) = self.lookup(ident) {
+            return Some(value.clone());
+        }
+        let var = self.loops.last()?;
+        match ident.to_string().as_str() {
+            "FIRST" => var.values.first()?.last().cloned(),
+            "LAST" => var.values.last()?.last().cloned(),
+            "COUNT" => Some(Value::Int(var.values.len() as i128)),
+            _ => None,
+        }
+    }
+}
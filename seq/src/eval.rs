@@ -0,0 +1,315 @@
+use crate::env::Env;
+use crate::parse::Value;
+use proc_macro2::{Group, Literal, Spacing, Span, TokenStream, TokenTree};
+use std::convert::TryFrom;
+use syn::{BinOp, Error, Expr, Lit, Result, UnOp};
+
+// How a number is written by a format spec like `:02` or `:x`.
+#[derive(Copy, Clone)]
+pub struct Spec {
+    width: usize,
+    radix: Radix,
+}
+
+#[derive(Copy, Clone)]
+enum Radix {
+    Decimal,
+    LowerHex,
+    UpperHex,
+    Binary,
+    Octal,
+}
+
+// The contents of `#(N * 4)` or `#(N:02x)`: integer arithmetic on the loop
+// variables, optionally followed by a format spec. It is parsed once and then
+// evaluated on every iteration.
+pub struct Expression {
+    expr: Expr,
+    spec: Option<Spec>,
+}
+
+pub fn evaluate(group: &Group, env: &Env) -> Result<(Value, Option<Spec>)> {
+    Expression::parse(group)?.evaluate(env)
+}
+
+impl Expression {
+    pub fn evaluate(&self, env: &Env) -> Result<(Value, Option<Spec>)> {
+        Ok((Value::Int(eval(&self.expr, env)?), self.spec))
+    }
+
+    pub fn parse(group: &Group) -> Result<Self> {
+        let tokens = group.stream().into_iter().collect::<Vec<_>>();
+        let (expr, spec) = match split_spec(&tokens) {
+            Some(colon) => match parse_spec(&tokens[colon + 1..], true) {
+                Some(spec) => (&tokens[..colon], Some(spec)),
+                None => {
+                    let msg = "expected a format spec like `:02`, `:x` or `:04b`";
+                    return Err(Error::new(tokens[colon].span(), msg));
+                }
+            },
+            None => (&tokens[..], None),
+        };
+        if expr.is_empty() {
+            return Err(Error::new(group.span(), "expected an expression"));
+        }
+        let expr: Expr = syn::parse2(expr.iter().cloned().collect())?;
+        Ok(Expression { expr, spec })
+    }
+}
+
+fn eval(expr: &Expr, env: &Env) -> Result<i128> {
+    let unsupported = || {
+        let msg = "unsupported expression, expected integer arithmetic on the loop variables";
+        Error::new_spanned(expr, msg)
+    };
+    let overflow = || Error::new_spanned(expr, "arithmetic overflow");
+    match expr {
+        Expr::Lit(expr) => match &expr.lit {
+            Lit::Int(lit) => lit.base10_parse(),
+            _ => Err(unsupported()),
+        },
+        Expr::Path(path) => {
+            let ident = match path.path.get_ident() {
+                Some(ident) if path.qself.is_none() => ident,
+                _ => return Err(unsupported()),
+            };
+            match env.resolve(ident) {
+                Some(Value::Int(value)) => Ok(value),
+                Some(Value::UInt(value)) => i128::try_from(value).map_err(|_| overflow()),
+                Some(_) => {
+                    let msg = format!("`{}` is not an integer", ident);
+                    Err(Error::new(ident.span(), msg))
+                }
+                None => {
+                    let msg = format!("`{}` is not a loop variable", ident);
+                    Err(Error::new(ident.span(), msg))
+                }
+            }
+        }
+        Expr::Paren(expr) => eval(&expr.expr, env),
+        Expr::Group(expr) => eval(&expr.expr, env),
+        Expr::Unary(unary) => {
+            let value = eval(&unary.expr, env)?;
+            match unary.op {
+                UnOp::Neg(_) => value.checked_neg().ok_or_else(overflow),
+                UnOp::Not(_) => Ok(!value),
+                _ => Err(unsupported()),
+            }
+        }
+        Expr::Binary(binary) => {
+            let left = eval(&binary.left, env)?;
+            let right = eval(&binary.right, env)?;
+            let shift = || u32::try_from(right).ok();
+            let value = match binary.op {
+                BinOp::Add(_) => left.checked_add(right),
+                BinOp::Sub(_) => left.checked_sub(right),
+                BinOp::Mul(_) => left.checked_mul(right),
+                BinOp::Div(_) => left.checked_div(right),
+                BinOp::Rem(_) => left.checked_rem(right),
+                BinOp::Shl(_) => shift().and_then(|right| left.checked_shl(right)),
+                BinOp::Shr(_) => shift().and_then(|right| left.checked_shr(right)),
+                BinOp::BitAnd(_) => Some(left & right),
+                BinOp::BitOr(_) => Some(left | right),
+                BinOp::BitXor(_) => Some(left ^ right),
+                _ => return Err(unsupported()),
+            };
+            value.ok_or_else(overflow)
+        }
+        _ => Err(unsupported()),
+    }
+}
+
+// Evaluates the condition of `#if`, like `N % 2 == 0 && N != LAST`. Integers
+// and characters can be compared in any way, list elements only for equality
+// by their tokens.
+pub fn condition(tokens: &[TokenTree], span: Span, env: &Env) -> Result<bool> {
+    if tokens.is_empty() {
+        return Err(Error::new(span, "expected a condition after `#if`"));
+    }
+    // Built-in variables can be written as `#LAST` like in the body, or just
+    // `LAST`.
+    let mut stream = TokenStream::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let pound = match token {
+            TokenTree::Punct(punct) => punct.as_char() == '#',
+            _ => false,
+        };
+        if !(pound && matches!(tokens.get(i + 1), Some(TokenTree::Ident(_)))) {
+            stream.extend([token.clone()]);
+        }
+    }
+    let expr: Expr = syn::parse2(stream)?;
+    test(&expr, env)
+}
+
+fn test(expr: &Expr, env: &Env) -> Result<bool> {
+    let unsupported = || {
+        let msg = "expected a condition like `N % 2 == 0` or `N == LAST`";
+        Error::new_spanned(expr, msg)
+    };
+    match expr {
+        Expr::Lit(expr) => match &expr.lit {
+            Lit::Bool(lit) => Ok(lit.value),
+            _ => Err(unsupported()),
+        },
+        Expr::Paren(expr) => test(&expr.expr, env),
+        Expr::Group(expr) => test(&expr.expr, env),
+        Expr::Unary(unary) => match unary.op {
+            UnOp::Not(_) => Ok(!test(&unary.expr, env)?),
+            _ => Err(unsupported()),
+        },
+        Expr::Binary(binary) => match binary.op {
+            BinOp::And(_) => Ok(test(&binary.left, env)? && test(&binary.right, env)?),
+            BinOp::Or(_) => Ok(test(&binary.left, env)? || test(&binary.right, env)?),
+            BinOp::Eq(_)
+            | BinOp::Ne(_)
+            | BinOp::Lt(_)
+            | BinOp::Le(_)
+            | BinOp::Gt(_)
+            | BinOp::Ge(_) => {
+                let left = operand(&binary.left, env)?;
+                let right = operand(&binary.right, env)?;
+                let ordering = match (left, right) {
+                    (Operand::Int(left), Operand::Int(right)) => left.cmp(&right),
+                    (Operand::Char(left), Operand::Char(right)) => left.cmp(&right),
+                    (Operand::Tokens(left), Operand::Tokens(right))
+                        if matches!(binary.op, BinOp::Eq(_) | BinOp::Ne(_)) =>
+                    {
+                        left.cmp(&right)
+                    }
+                    _ => {
+                        let msg = "cannot compare these values";
+                        return Err(Error::new_spanned(expr, msg));
+                    }
+                };
+                Ok(match binary.op {
+                    BinOp::Eq(_) => ordering.is_eq(),
+                    BinOp::Ne(_) => ordering.is_ne(),
+                    BinOp::Lt(_) => ordering.is_lt(),
+                    BinOp::Le(_) => ordering.is_le(),
+                    BinOp::Gt(_) => ordering.is_gt(),
+                    _ => ordering.is_ge(),
+                })
+            }
+            _ => Err(unsupported()),
+        },
+        _ => Err(unsupported()),
+    }
+}
+
+enum Operand {
+    Int(i128),
+    Char(char),
+    Tokens(String),
+}
+
+fn operand(expr: &Expr, env: &Env) -> Result<Operand> {
+    if let Expr::Lit(lit) = expr {
+        if let Lit::Char(lit) = &lit.lit {
+            return Ok(Operand::Char(lit.value()));
+        }
+    }
+    if let Expr::Path(path) = expr {
+        if let Some(ident) = path.path.get_ident() {
+            match env.resolve(ident) {
+                Some(Value::Char(value)) => return Ok(Operand::Char(value)),
+                Some(Value::Tokens(tokens)) => return Ok(Operand::Tokens(tokens.to_string())),
+                Some(_) => {}
+                // An identifier that is not a variable is compared to list
+                // elements, as in `T == u8`.
+                None => return Ok(Operand::Tokens(ident.to_string())),
+            }
+        }
+    }
+    eval(expr, env).map(Operand::Int)
+}
+
+// The position of the `:` that starts a format spec, not counting the colons
+// of a `::` path separator.
+fn split_spec(tokens: &[TokenTree]) -> Option<usize> {
+    (0..tokens.len()).rev().find(|&i| {
+        let is_colon = |token: &TokenTree| match token {
+            TokenTree::Punct(punct) => punct.as_char() == ':',
+            _ => false,
+        };
+        let joint = |token: &TokenTree| match token {
+            TokenTree::Punct(punct) => punct.spacing() == Spacing::Joint,
+            _ => false,
+        };
+        is_colon(&tokens[i])
+            && !joint(&tokens[i])
+            && !(i > 0 && is_colon(&tokens[i - 1]) && joint(&tokens[i - 1]))
+    })
+}
+
+// Parses the tokens after the `:` of a format spec, which must be all of
+// `tokens`. Outside of #(...), a spec follows a pasted variable in places like
+// `field#N: X` where an identifier after the colon is more likely to be a
+// type, so `letters` only allows `x`, `b` and `o` on their own when false.
+pub fn parse_spec(tokens: &[TokenTree], letters: bool) -> Option<Spec> {
+    let (width, radix) = match tokens {
+        [TokenTree::Literal(literal)] => {
+            let repr = literal.to_string();
+            let digits = repr.bytes().take_while(u8::is_ascii_digit).count();
+            (repr[..digits].parse().ok()?, radix(&repr[digits..])?)
+        }
+        [TokenTree::Ident(ident)] => {
+            let repr = ident.to_string();
+            if !letters && repr == "X" {
+                return None;
+            }
+            (0, radix(&repr).filter(|_| !repr.is_empty())?)
+        }
+        _ => return None,
+    };
+    Some(Spec { width, radix })
+}
+
+fn radix(suffix: &str) -> Option<Radix> {
+    match suffix {
+        "" => Some(Radix::Decimal),
+        "x" => Some(Radix::LowerHex),
+        "X" => Some(Radix::UpperHex),
+        "b" => Some(Radix::Binary),
+        "o" => Some(Radix::Octal),
+        _ => None,
+    }
+}
+
+// The digits of an integer written according to `spec`, padded with zeros.
+pub fn format(value: &Value, spec: &Spec, span: Span) -> Result<String> {
+    let (negative, magnitude) = match value {
+        Value::Int(value) => (*value < 0, value.unsigned_abs()),
+        Value::UInt(value) => (false, *value),
+        _ => return Err(Error::new(span, "a format spec only applies to integers")),
+    };
+    let sign = if negative { "-" } else { "" };
+    let width = spec.width.saturating_sub(sign.len());
+    let digits = match spec.radix {
+        Radix::Decimal => format!("{:0width$}", magnitude, width = width),
+        Radix::LowerHex => format!("{:0width$x}", magnitude, width = width),
+        Radix::UpperHex => format!("{:0width$X}", magnitude, width = width),
+        Radix::Binary => format!("{:0width$b}", magnitude, width = width),
+        Radix::Octal => format!("{:0width$o}", magnitude, width = width),
+    };
+    Ok(format!("{}{}", sign, digits))
+}
+
+// A formatted integer as a literal, with the prefix of its radix so that it
+// keeps its value, like `0x1f` or `007`.
+pub fn literal(value: &Value, spec: &Spec, span: Span) -> Result<TokenStream> {
+    let digits = format(value, spec, span)?;
+    let prefix = match spec.radix {
+        Radix::Decimal => "",
+        Radix::LowerHex | Radix::UpperHex => "0x",
+        Radix::Binary => "0b",
+        Radix::Octal => "0o",
+    };
+    let repr = match digits.strip_prefix('-') {
+        Some(digits) => format!("-{}{}", prefix, digits),
+        None => format!("{}{}", prefix, digits),
+    };
+    let mut literal: Literal = repr.parse().expect("formatted integer literal");
+    literal.set_span(span);
+    Ok(TokenTree::Literal(literal).into())
+}
@@ -0,0 +1,607 @@
+use crate::case::Case;
+use crate::env::Env;
+use crate::eval::{self, Expression, Spec};
+use crate::lint;
+use crate::parse::{self, Seq, Value, Var};
+use proc_macro2::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+use syn::ext::IdentExt;
+use syn::parse::Parser;
+use syn::{Error, Lit, LitStr, Result};
+
+pub fn expand(seq: &Seq) -> Result<TokenStream> {
+    let vars = seq.header.vars.iter().collect::<Vec<_>>();
+    let names = vars
+        .iter()
+        .flat_map(|var| &var.idents)
+        .map(Ident::to_string)
+        .collect::<Vec<_>>();
+    let body = Template::new(&seq.body, &names)?;
+    let mut env = Env::new(seq.header.limit);
+    // Outside of any #(...)* section, the built-in variables describe the
+    // variable that the top-level sections iterate.
+    if let (true, Some(var)) = (body.has_section, vars.first()) {
+        env.enter(var);
+    }
+    let mut output = repeat(&body, &mut env, &vars)?;
+    lint::emit(&mut output, lint::check(seq));
+    Ok(output)
+}
+
+// The body of the macro, split into tokens once rather than on every
+// iteration. Groups that do not depend on the loop variables, which is most
+// of them in a large table, are copied as they are.
+struct Template {
+    tokens: Vec<TokenTree>,
+    // The template of each group among `tokens`, at the same index.
+    groups: Vec<Option<Template>>,
+    // The parsed #(...) expression that starts at each index, if any.
+    expressions: Vec<Option<Expression>>,
+    // Whether anything inside refers to a loop variable or starts with `#`.
+    dynamic: bool,
+    has_section: bool,
+}
+
+impl Template {
+    fn new(stream: &TokenStream, names: &[String]) -> Result<Self> {
+        let tokens = stream.clone().into_iter().collect::<Vec<_>>();
+        let mut groups = Vec::with_capacity(tokens.len());
+        let mut expressions = Vec::with_capacity(tokens.len());
+        let mut dynamic = false;
+        let mut has_section = false;
+        for (i, token) in tokens.iter().enumerate() {
+            has_section |= section(&tokens[i..]).is_some();
+            expressions.push(match expression(&tokens[i..]) {
+                Some(group) => Some(Expression::parse(group)?),
+                None => None,
+            });
+            let group = match token {
+                TokenTree::Group(group) => {
+                    let template = Template::new(&group.stream(), names)?;
+                    dynamic |= template.dynamic;
+                    has_section |= template.has_section;
+                    Some(template)
+                }
+                TokenTree::Punct(punct) => {
+                    dynamic |= punct.as_char() == '#';
+                    None
+                }
+                TokenTree::Ident(ident) => {
+                    dynamic |= names.iter().any(|name| ident == name);
+                    None
+                }
+                TokenTree::Literal(literal) => {
+                    dynamic |= literal.to_string().contains('#');
+                    None
+                }
+            };
+            groups.push(group);
+        }
+        Ok(Template {
+            tokens,
+            groups,
+            expressions,
+            dynamic,
+            has_section,
+        })
+    }
+
+    fn group(&self, i: usize) -> &Template {
+        self.groups[i].as_ref().expect("template of a group")
+    }
+}
+
+// Expands `body` once for every combination of values of the `remaining`
+// variables, unless it contains #(...)* sections, in which case those do the
+// repeating instead: each section iterates the first remaining variable and
+// leaves the rest to sections nested inside it.
+fn repeat<'a>(body: &Template, env: &mut Env<'a>, remaining: &[&'a Var]) -> Result<TokenStream> {
+    if body.has_section {
+        return substitute(body, env, remaining);
+    }
+    match remaining.split_first() {
+        None => substitute(body, env, remaining),
+        Some((var, rest)) => each(var, env, None, |env| repeat(body, env, rest)),
+    }
+}
+
+// Concatenates the expansions of `f` with `var` bound to each of its values,
+// with `separator` in between.
+fn each<'a>(
+    var: &'a Var,
+    env: &mut Env<'a>,
+    separator: Option<&Punct>,
+    mut f: impl FnMut(&mut Env<'a>) -> Result<TokenStream>,
+) -> Result<TokenStream> {
+    if !env.count(var.values.len()) {
+        return Err(parse::limit_exceeded(&var.idents[0], env.limit()));
+    }
+    let mut output = TokenStream::new();
+    env.enter(var);
+    for (i, values) in var.values.iter().enumerate() {
+        if let (true, Some(separator)) = (i > 0, separator) {
+            // The separator is joint with the `*` after it in the input, but
+            // must not combine with whatever follows it in the output.
+            let mut punct = Punct::new(separator.as_char(), Spacing::Alone);
+            punct.set_span(separator.span());
+            output.extend([TokenTree::Punct(punct)]);
+        }
+        env.bind(var, values);
+        let expanded = f(env);
+        env.unbind(var);
+        output.extend(expanded?);
+    }
+    env.exit();
+    Ok(output)
+}
+
+fn substitute<'a>(
+    template: &Template,
+    env: &mut Env<'a>,
+    remaining: &[&'a Var],
+) -> Result<TokenStream> {
+    if !template.dynamic {
+        return Ok(template.tokens.iter().cloned().collect());
+    }
+    let tokens = &template.tokens;
+    let mut output = TokenStream::new();
+    let mut i = 0;
+    while i < tokens.len() {
+        if let Some(section) = section(&tokens[i..]) {
+            let (var, rest) = match remaining.split_first() {
+                Some(split) => split,
+                None => {
+                    let msg = "no loop variable left for this repetition to iterate";
+                    return Err(Error::new(tokens[i].span(), msg));
+                }
+            };
+            let body = template.group(i + 1);
+            let separator = section.separator;
+            output.extend(each(var, env, separator, |env| {
+                substitute(body, env, rest)
+            })?);
+            i += section.len;
+            continue;
+        }
+        if let Some((branch, len)) = conditional(&tokens[i..], env)? {
+            if let Some(branch) = branch {
+                output.extend(substitute(template.group(i + branch), env, remaining)?);
+            }
+            i += len;
+            continue;
+        }
+        if let [TokenTree::Punct(pound), TokenTree::Ident(ident), ..] = &tokens[i..] {
+            if pound.as_char() == '#' {
+                if ident == "else" {
+                    return Err(Error::new(ident.span(), "`#else` without `#if`"));
+                }
+                if let Some(value) = env.resolve(ident) {
+                    match Case::parse(&tokens[i + 2..]) {
+                        Some(case) => {
+                            let text = case.apply(&value.to_ident_fragment(ident.span())?);
+                            output.extend([TokenTree::Ident(paste_ident(&text, ident.span())?)]);
+                            i += 4;
+                        }
+                        None => {
+                            let (ty, len) = match suffix(&tokens[i + 2..]) {
+                                Some(ty) => (Some(ty), 4),
+                                None => (env.ty(ident), 2),
+                            };
+                            output.extend(emit(&value, ident.span(), ty)?);
+                            i += len;
+                        }
+                    }
+                    continue;
+                }
+            }
+        }
+        if let Some(expression) = &template.expressions[i] {
+            let span = tokens[i + 1].span();
+            let (value, spec) = expression.evaluate(env)?;
+            match spec {
+                Some(spec) => output.extend(eval::literal(&value, &spec, span)?),
+                None => output.extend(value.to_tokens(span)),
+            }
+            i += 2;
+            continue;
+        }
+        match &tokens[i] {
+            TokenTree::Ident(ident) => {
+                let (pasted, len) = paste(&tokens[i..], env)?;
+                for token in &tokens[i..i + len] {
+                    if let TokenTree::Ident(ident) = token {
+                        check_bound(ident, env, remaining)?;
+                    }
+                }
+                match pasted {
+                    Some(pasted) => output.extend([TokenTree::Ident(pasted)]),
+                    None => match env.lookup(ident) {
+                        Some(value) => output.extend(emit(value, ident.span(), env.ty(ident))?),
+                        None => output.extend([tokens[i].clone()]),
+                    },
+                }
+                i += len;
+            }
+            TokenTree::Group(group) if template.group(i).dynamic => {
+                let stream = substitute(template.group(i), env, remaining)?;
+                let mut substituted = Group::new(group.delimiter(), stream);
+                substituted.set_span(group.span());
+                output.extend([TokenTree::Group(substituted)]);
+                i += 1;
+            }
+            TokenTree::Literal(literal) => {
+                output.extend([interpolate(literal, env)]);
+                i += 1;
+            }
+            token => {
+                output.extend([token.clone()]);
+                i += 1;
+            }
+        }
+    }
+    Ok(output)
+}
+
+// A variable that is left for a #(...)* section to iterate has no value
+// outside of that section, as in `#(reg_#N#_#M)*` with no section for M
+// inside, where it would otherwise stay in the output as it is.
+fn check_bound(ident: &Ident, env: &Env, remaining: &[&Var]) -> Result<()> {
+    if env.lookup(ident).is_none() && remaining.iter().any(|var| var.idents.contains(ident)) {
+        let msg = format!(
+            "`{}` is not iterated here, it needs a #(...)* section of its own",
+            ident,
+        );
+        return Err(Error::new(ident.span(), msg));
+    }
+    Ok(())
+}
+
+fn emit(value: &Value, span: Span, ty: Option<&Ident>) -> Result<TokenStream> {
+    match ty {
+        Some(ty) => value.to_suffixed_tokens(span, ty),
+        None => Ok(value.to_tokens(span)),
+    }
+}
+
+// Recognizes `: usize` after `#N`, which makes the literal of a single
+// occurrence of a variable typed.
+fn suffix(tokens: &[TokenTree]) -> Option<&Ident> {
+    match tokens {
+        [TokenTree::Punct(colon), TokenTree::Ident(ty), ..]
+            if colon.as_char() == ':'
+                && colon.spacing() == Spacing::Alone
+                && parse::int_bounds(ty).is_some() =>
+        {
+            Some(ty)
+        }
+        _ => None,
+    }
+}
+
+// The body of a section is the group after the `#`.
+pub struct Section<'a> {
+    separator: Option<&'a Punct>,
+    // Number of tokens, including `#` and `*`.
+    len: usize,
+}
+
+// Recognizes `# ( ... ) *` at the start of `tokens`, or `# ( ... ) , *` with
+// any single punctuation character as a separator like in macro_rules.
+pub fn section(tokens: &[TokenTree]) -> Option<Section<'_>> {
+    let (pound, group, rest) = match tokens {
+        [TokenTree::Punct(pound), TokenTree::Group(group), rest @ ..] => (pound, group, rest),
+        _ => return None,
+    };
+    if pound.as_char() != '#' || group.delimiter() != Delimiter::Parenthesis {
+        return None;
+    }
+    let is_star = |punct: &Punct| punct.as_char() == '*';
+    let separator = match rest {
+        [TokenTree::Punct(star), ..] if is_star(star) => None,
+        [TokenTree::Punct(separator), TokenTree::Punct(star), ..]
+            if is_star(star) && !is_star(separator) =>
+        {
+            Some(separator)
+        }
+        _ => return None,
+    };
+    Some(Section {
+        separator,
+        len: 3 + separator.is_some() as usize,
+    })
+}
+
+// Pastes `f # N # _ # M` into a single identifier like `f0_3`, substituting
+// the loop variables among the pieces. A piece can also be an expression like
+// `#(N * 4)`, and a variable can be followed by a format spec, as in
+// `Reg#N:02` or `irq_#N:x`, or by a case modifier, as in `read_#T:snake`.
+// Returns None if `tokens` does not start with an identifier followed by `#`
+// and another piece. Otherwise also returns how many tokens were consumed.
+//
+// A spec or modifier is written without spaces around its `:`. In a struct
+// literal like `Regs { reg#N: 7 }`, the `: 7` is a field value.
+//
+// The pasted identifier takes the span of the first piece, so that errors
+// about it point at what the caller wrote. A keyword never starts a paste, so
+// that `struct #T` or `fn #T:snake()` substitute the variable on its own.
+fn paste(tokens: &[TokenTree], env: &Env) -> Result<(Option<Ident>, usize)> {
+    let first = match &tokens[0] {
+        TokenTree::Ident(ident) => ident,
+        _ => return Ok((None, 1)),
+    };
+    let mut pieces = vec![(Piece::Ident(first), None)];
+    let mut len = 1;
+    loop {
+        let rest = &tokens[len..];
+        match rest {
+            [TokenTree::Punct(pound), TokenTree::Ident(next), ..]
+                if pound.as_char() == '#' && pound.spacing() == Spacing::Alone =>
+            {
+                len += 2;
+                let attached = attached(next, &tokens[len..]);
+                if let (true, Some(case), Some(_)) =
+                    (attached, Case::parse(&tokens[len..]), env.resolve(next))
+                {
+                    len += 2;
+                    pieces.push((Piece::Ident(next), Some(Modifier::Case(case))));
+                    continue;
+                }
+                let spec = match &tokens[len..] {
+                    [TokenTree::Punct(colon), spec, ..]
+                        if colon.as_char() == ':'
+                            && colon.spacing() == Spacing::Alone
+                            && attached
+                            && env.resolve(next).is_some() =>
+                    {
+                        eval::parse_spec(std::slice::from_ref(spec), false)
+                    }
+                    _ => None,
+                };
+                if spec.is_some() {
+                    len += 2;
+                }
+                pieces.push((Piece::Ident(next), spec.map(Modifier::Spec)));
+            }
+            _ => match expression(rest) {
+                Some(group) => {
+                    len += 2;
+                    pieces.push((Piece::Expr(group), None));
+                }
+                None => break,
+            },
+        }
+    }
+    if pieces.len() == 1 || is_keyword(first) {
+        return Ok((None, 1));
+    }
+    let mut text = String::new();
+    for (piece, modifier) in pieces {
+        text += &fragment(piece, modifier, env)?;
+    }
+    let pasted = paste_ident(&text, first.span())?;
+    Ok((Some(pasted), len))
+}
+
+// Whether `tokens` start with a `:` and another token that directly follow
+// `ident` in the source, as in `N:02`.
+fn attached(ident: &Ident, tokens: &[TokenTree]) -> bool {
+    match tokens {
+        [colon, next, ..] => {
+            ident.span().end() == colon.span().start() && colon.span().end() == next.span().start()
+        }
+        _ => false,
+    }
+}
+
+enum Piece<'a> {
+    Ident(&'a Ident),
+    Expr(&'a Group),
+}
+
+enum Modifier {
+    Spec(Spec),
+    Case(Case),
+}
+
+fn fragment(piece: Piece, modifier: Option<Modifier>, env: &Env) -> Result<String> {
+    let (value, modifier, span) = match piece {
+        Piece::Ident(ident) => match env.resolve(ident) {
+            Some(value) => (value, modifier, ident.span()),
+            None => return Ok(ident.unraw().to_string()),
+        },
+        Piece::Expr(group) => {
+            let (value, spec) = eval::evaluate(group, env)?;
+            (value, spec.map(Modifier::Spec), group.span())
+        }
+    };
+    match modifier {
+        Some(Modifier::Spec(spec)) => eval::format(&value, &spec, span),
+        Some(Modifier::Case(case)) => Ok(case.apply(&value.to_ident_fragment(span)?)),
+        None => value.to_ident_fragment(span),
+    }
+}
+
+// Substitutes `#N` inside a string literal, including the ones that doc
+// comments turn into, as in `/// Interrupt line #N`. A variable can be
+// followed by a case modifier like `#T:snake`. Any other `#` is left alone.
+fn interpolate(literal: &Literal, env: &Env) -> TokenTree {
+    let unchanged = || TokenTree::Literal(literal.clone());
+    let lit = match Lit::new(literal.clone()) {
+        Lit::Str(lit) if lit.suffix().is_empty() => lit,
+        _ => return unchanged(),
+    };
+    let value = lit.value();
+    let mut text = String::new();
+    let mut rest = value.as_str();
+    let mut changed = false;
+    while let Some(pound) = rest.find('#') {
+        text += &rest[..pound];
+        let after = &rest[pound + 1..];
+        let name = &after[..ident_len(after)];
+        let value = if name.is_empty() {
+            None
+        } else {
+            env.resolve(&Ident::new(name, literal.span()))
+        };
+        let value = match value {
+            Some(value) => value,
+            None => {
+                text.push('#');
+                rest = after;
+                continue;
+            }
+        };
+        let mut fragment = value.to_text();
+        rest = &after[name.len()..];
+        if let Some(modifier) = rest.strip_prefix(':') {
+            if let Some(case) = Case::from_name(&modifier[..ident_len(modifier)]) {
+                fragment = case.apply(&fragment);
+                rest = &modifier[ident_len(modifier)..];
+            }
+        }
+        text += &fragment;
+        changed = true;
+    }
+    if !changed {
+        return unchanged();
+    }
+    text += rest;
+    TokenTree::Literal(LitStr::new(&text, literal.span()).token())
+}
+
+// The length of the identifier at the start of `text`, if any.
+fn ident_len(text: &str) -> usize {
+    match text.chars().next() {
+        Some(ch) if ch == '_' || ch.is_ascii_alphabetic() => text
+            .find(|ch: char| ch != '_' && !ch.is_ascii_alphanumeric())
+            .unwrap_or(text.len()),
+        _ => 0,
+    }
+}
+
+// Recognizes `#if condition { ... }` at the start of `tokens`, optionally
+// followed by `#else { ... }` or `#else #if ...`, and returns the index of
+// the group of the branch that the condition selects, if any, along with the
+// number of tokens consumed.
+fn conditional(tokens: &[TokenTree], env: &Env) -> Result<Option<(Option<usize>, usize)>> {
+    let keyword = match tokens {
+        [TokenTree::Punct(pound), TokenTree::Ident(keyword), ..]
+            if pound.as_char() == '#' && keyword == "if" =>
+        {
+            keyword
+        }
+        _ => return Ok(None),
+    };
+    let is_brace = |token: &TokenTree| match token {
+        TokenTree::Group(group) => group.delimiter() == Delimiter::Brace,
+        _ => false,
+    };
+    let mut len = 2;
+    while len < tokens.len() && !is_brace(&tokens[len]) {
+        len += 1;
+    }
+    if len == tokens.len() {
+        let msg = "expected `{` after the condition of `#if`";
+        return Err(Error::new(keyword.span(), msg));
+    }
+    let then_branch = Some(len);
+    let holds = eval::condition(&tokens[2..len], keyword.span(), env)?;
+    len += 1;
+
+    let mut else_branch = None;
+    if let [TokenTree::Punct(pound), TokenTree::Ident(keyword), rest @ ..] = &tokens[len..] {
+        if pound.as_char() == '#' && keyword == "else" {
+            len += 2;
+            match rest {
+                [first, ..] if is_brace(first) => {
+                    else_branch = Some(len);
+                    len += 1;
+                }
+                _ => match conditional(rest, env)? {
+                    Some((branch, rest_len)) => {
+                        else_branch = branch.map(|branch| len + branch);
+                        len += rest_len;
+                    }
+                    None => {
+                        let msg = "expected `{` or `#if` after `#else`";
+                        return Err(Error::new(keyword.span(), msg));
+                    }
+                },
+            }
+        }
+    }
+
+    let branch = if holds { then_branch } else { else_branch };
+    Ok(Some((branch, len)))
+}
+
+// Recognizes `# ( ... )` that is not a #(...)* section at the start of
+// `tokens`. Like in macro_rules, `#(...),*` is always a section with a
+// separator.
+fn expression(tokens: &[TokenTree]) -> Option<&Group> {
+    if section(tokens).is_some() {
+        return None;
+    }
+    match tokens {
+        [TokenTree::Punct(pound), TokenTree::Group(group), ..]
+            if pound.as_char() == '#' && group.delimiter() == Delimiter::Parenthesis =>
+        {
+            Some(group)
+        }
+        _ => None,
+    }
+}
+
+fn is_keyword(ident: &Ident) -> bool {
+    syn::parse2::<Ident>(TokenTree::Ident(ident.clone()).into()).is_err()
+}
+
+fn paste_ident(text: &str, span: Span) -> Result<Ident> {
+    match Ident::parse_any.parse_str(text) {
+        Ok(mut ident) => {
+            ident.set_span(span);
+            Ok(ident)
+        }
+        Err(_) => {
+            let msg = format!("`{}` is not a valid identifier", text);
+            Err(Error::new(span, msg))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::expand;
+    use crate::parse::Seq;
+    use std::time::{Duration, Instant};
+
+    fn time(len: usize) -> Duration {
+        let seq: Seq = syn::parse_str(&format!(
+            r#"N in 0..{len}, limit = {limit} {{
+                static SQUARES: [u32; {len}] = [#(N * N,)*];
+                static BYTES: [u8; {len}] = [#(#(N % 256),)*];
+                static NAMES: [&str; {len}] = [#("n#N",)*];
+            }}"#,
+            len = len,
+            limit = len * 3,
+        ))
+        .unwrap();
+        let start = Instant::now();
+        expand(&seq).unwrap();
+        start.elapsed()
+    }
+
+    // The tables of tests/20-large-ranges.rs, which take well under a second
+    // to expand in a debug build. The cost has to grow linearly with the
+    // number of iterations, which is compared with a table 16 times smaller,
+    // and the absolute bound catches everything getting much slower. Timings
+    // vary with the load on the machine, so this only runs on request, with
+    // `cargo test -- --ignored`.
+    #[test]
+    #[ignore = "measures time"]
+    fn large_expansion_time() {
+        let small = time(4096);
+        let large = time(65536);
+        assert!(large < small * 48, "{:?} vs. {:?}", large, small);
+        assert!(large < Duration::from_secs(5), "took {:?}", large);
+    }
+}
@@ -1,10 +1,60 @@
 extern crate proc_macro;
 
+mod attr;
+mod case;
+mod env;
+mod eval;
+mod expand;
+mod lint;
+mod lookup;
+mod parse;
+mod range;
+
+use crate::lookup::{Dispatch, Table};
+use crate::parse::{Header, Seq};
 use proc_macro::TokenStream;
+use syn::parse_macro_input;
 
 #[proc_macro]
 pub fn seq(input: TokenStream) -> TokenStream {
-    let _ = input;
+    let input = parse_macro_input!(input as Seq);
+    expand::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro]
+pub fn dispatch(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as Dispatch);
+    lookup::dispatch(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro]
+pub fn table(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as Table);
+    lookup::table(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_attribute]
+pub fn repeat(args: TokenStream, input: TokenStream) -> TokenStream {
+    let header = parse_macro_input!(args as Header);
+    attr::repeat(header, input.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-    unimplemented!()
+// Only meaningful inside an item with #[seq::repeat(...)], which removes it
+// before the compiler would get to expand it.
+#[proc_macro_attribute]
+pub fn each(_args: TokenStream, input: TokenStream) -> TokenStream {
+    let msg = "`#[seq::each]` must be inside an item with `#[seq::repeat(...)]`";
+    let mut output: TokenStream = syn::Error::new(proc_macro2::Span::call_site(), msg)
+        .into_compile_error()
+        .into();
+    output.extend(input);
+    output
 }
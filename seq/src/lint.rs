@@ -0,0 +1,207 @@
+use crate::expand;
+use crate::parse::{Seq, Var};
+use proc_macro2::{Delimiter, Group, Ident, Spacing, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote, quote_spanned};
+use syn::parse::Parser;
+use syn::{Block, Stmt};
+
+pub struct Warning {
+    name: &'static str,
+    span: Span,
+    message: String,
+}
+
+// A loop variable that the body never mentions, and a #(...)* section that
+// does not mention the variable it iterates while other parts of the body
+// do. Either way the same tokens come out for every value, which is more
+// likely a typo than intended. Naming the variable with a leading underscore,
+// like `_N`, says that it is intended, as in `[#(0,)*]` for an array of zeros.
+pub fn check(seq: &Seq) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    for var in &seq.header.vars {
+        if !intended(var) && !mentions(&seq.body, var) {
+            let ident = &var.idents[var.idents.len() - 1];
+            warnings.push(Warning {
+                name: "unused_loop_variable",
+                span: ident.span(),
+                message: format!(
+                    "loop variable `{}` is never used; if that is intended, name it `_{}`",
+                    ident, ident,
+                ),
+            });
+        }
+    }
+    sections(&seq.body, &seq.body, &seq.header.vars, &mut warnings);
+    warnings
+}
+
+fn sections(
+    body: &TokenStream,
+    tokens: &TokenStream,
+    remaining: &[Var],
+    warnings: &mut Vec<Warning>,
+) {
+    let tokens = tokens.clone().into_iter().collect::<Vec<_>>();
+    for (i, token) in tokens.iter().enumerate() {
+        let group = match token {
+            TokenTree::Group(group) => group,
+            _ => continue,
+        };
+        let is_section = i > 0 && expand::section(&tokens[i - 1..]).is_some();
+        match remaining.split_first() {
+            Some((var, rest)) if is_section => {
+                if !intended(var) && !mentions(&group.stream(), var) && mentions(body, var) {
+                    let ident = &var.idents[var.idents.len() - 1];
+                    warnings.push(Warning {
+                        name: "repetition_without_variable",
+                        span: tokens[i - 1].span(),
+                        message: format!(
+                            "this repetition never mentions `{}`; if that is intended, name it `_{}`",
+                            ident, ident,
+                        ),
+                    });
+                }
+                sections(body, &group.stream(), rest, warnings);
+            }
+            _ => sections(body, &group.stream(), remaining, warnings),
+        }
+    }
+}
+
+fn intended(var: &Var) -> bool {
+    var.idents
+        .iter()
+        .any(|ident| ident.to_string().starts_with('_'))
+}
+
+fn mentions(tokens: &TokenStream, var: &Var) -> bool {
+    tokens.clone().into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => var.idents.contains(&ident),
+        TokenTree::Literal(literal) => {
+            let repr = literal.to_string();
+            var.idents
+                .iter()
+                .any(|ident| repr.contains(&format!("#{}", ident)))
+        }
+        TokenTree::Group(group) => mentions(&group.stream(), var),
+        TokenTree::Punct(_) => false,
+    })
+}
+
+// There is no way to emit a warning from a proc macro on stable, so call a
+// deprecated function at the span instead, whose deprecation note carries
+// the message. The call goes at the start of the first function body in the
+// expansion, since that is allowed no matter whether seq! is invoked in a
+// module, a block, an impl or a trait. Without a function body, an expansion
+// that is more than items, like `println!("hi");`, is in a block, so the call
+// goes before it, and a lone expression like `N + 1` is put in a block with
+// it. An expansion of only items without a function body, like a few
+// statics, gets no warning, since it may be in an impl or a trait.
+pub fn emit(output: &mut TokenStream, warnings: Vec<Warning>) {
+    if warnings.is_empty() {
+        return;
+    }
+    let mut emitted = TokenStream::new();
+    for warning in warnings {
+        let name = format_ident!("{}", warning.name);
+        let call = Ident::new(warning.name, warning.span);
+        let call = quote_spanned!(warning.span=> #call());
+        let message = warning.message;
+        emitted.extend(quote! {
+            const _: () = {
+                #[deprecated(note = #message)]
+                const fn #name() {}
+                #call;
+            };
+        });
+    }
+    if let Some(injected) = inject(output, &emitted) {
+        *output = injected;
+        return;
+    }
+    let statements = match Block::parse_within.parse2(output.clone()) {
+        Ok(statements) => statements,
+        Err(_) => return,
+    };
+    match statements.as_slice() {
+        [Stmt::Expr(_, None)] => *output = quote!({ #emitted #output }),
+        _ if statements.iter().any(|stmt| !matches!(stmt, Stmt::Item(_))) => {
+            emitted.extend(output.clone());
+            *output = emitted;
+        }
+        _ => {}
+    }
+}
+
+// Puts `statements` at the start of the first function body in `tokens`,
+// looking into the bodies of impls, traits and modules too.
+fn inject(tokens: &TokenStream, statements: &TokenStream) -> Option<TokenStream> {
+    let mut tokens = tokens.clone().into_iter().collect::<Vec<_>>();
+    for i in 0..tokens.len() {
+        let body = match &tokens[i] {
+            TokenTree::Ident(ident) if ident == "fn" => function_body(&tokens[i + 1..]),
+            _ => None,
+        };
+        if let Some(body) = body {
+            let body = &mut tokens[i + 1 + body];
+            let mut stream = statements.clone();
+            stream.extend(stream_of(body));
+            replace_stream(body, stream);
+            return Some(tokens.into_iter().collect());
+        }
+        if let TokenTree::Group(group) = &tokens[i] {
+            if group.delimiter() == Delimiter::Brace {
+                if let Some(stream) = inject(&group.stream(), statements) {
+                    replace_stream(&mut tokens[i], stream);
+                    return Some(tokens.into_iter().collect());
+                }
+            }
+        }
+    }
+    None
+}
+
+fn stream_of(token: &TokenTree) -> TokenStream {
+    match token {
+        TokenTree::Group(group) => group.stream(),
+        _ => TokenStream::new(),
+    }
+}
+
+fn replace_stream(token: &mut TokenTree, stream: TokenStream) {
+    let mut group = Group::new(Delimiter::Brace, stream);
+    group.set_span(token.span());
+    *token = TokenTree::Group(group);
+}
+
+// The index of the body of a function whose signature starts `tokens`, or
+// None for a function without a body, like `fn f();` in a trait, or for a
+// function pointer type like `fn(u8)`.
+fn function_body(tokens: &[TokenTree]) -> Option<usize> {
+    let mut angle_brackets = 0;
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Group(group)
+                if group.delimiter() == Delimiter::Brace && angle_brackets == 0 =>
+            {
+                return Some(i);
+            }
+            TokenTree::Punct(punct) => match punct.as_char() {
+                '<' => angle_brackets += 1,
+                // Not the `>` of `->`.
+                '>' if !is_joint_minus(tokens, i) => angle_brackets -= 1,
+                ';' | ',' | '=' if angle_brackets == 0 => return None,
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    None
+}
+
+fn is_joint_minus(tokens: &[TokenTree], i: usize) -> bool {
+    match i.checked_sub(1).map(|prev| &tokens[prev]) {
+        Some(TokenTree::Punct(prev)) => prev.as_char() == '-' && prev.spacing() == Spacing::Joint,
+        _ => false,
+    }
+}
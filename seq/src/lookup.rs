@@ -0,0 +1,166 @@
+use crate::expand;
+use crate::parse::{self, Header, Seq};
+use proc_macro2::{Delimiter, Group, Literal, Punct, Spacing, TokenStream, TokenTree};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::{Error, Expr, Result, Token};
+
+// dispatch!(n, N in 0..16 => f#N(), _ => fallback)
+pub struct Dispatch {
+    scrutinee: Expr,
+    header: Header,
+    arm: TokenStream,
+    fallback: Option<Expr>,
+}
+
+// table!(static HANDLERS: [fn()] = N in 0..16 => f#N)
+pub struct Table {
+    // Everything before the element type, like `pub static HANDLERS:`.
+    item: TokenStream,
+    element_type: Group,
+    header: Header,
+    element: TokenStream,
+}
+
+impl Parse for Dispatch {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.is_empty() || input.peek(Token![,]) {
+            return Err(input.error(
+                "expected the value to dispatch on, as in `dispatch!(n, N in 0..4 => f#N())`",
+            ));
+        }
+        let scrutinee: Expr = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let header = single(input)?;
+        input.parse::<Token![=>]>()?;
+        // The arm is not Rust until the loop variable is substituted, so it
+        // cannot be parsed as an expression. It ends at the comma before the
+        // fallback arm or at the end, which leaves commas in generic
+        // arguments, like those of `f::<A, B>()`, in the arm.
+        let arm = until(input, |input| {
+            let fork = input.fork();
+            fork.parse::<Token![,]>().is_ok()
+                && (fork.is_empty() || fork.peek(Token![_]) && fork.peek2(Token![=>]))
+        })?;
+        input.parse::<Option<Token![,]>>()?;
+        let fallback = if input.is_empty() {
+            None
+        } else {
+            input.parse::<Token![_]>()?;
+            input.parse::<Token![=>]>()?;
+            let fallback: Expr = input.parse()?;
+            input.parse::<Option<Token![,]>>()?;
+            Some(fallback)
+        };
+        Ok(Dispatch {
+            scrutinee,
+            header,
+            arm,
+            fallback,
+        })
+    }
+}
+
+impl Parse for Table {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut item = until(input, |input| {
+            input.peek(Token![=]) && !input.peek(Token![=>])
+        })?
+        .into_iter()
+        .collect::<Vec<_>>();
+        let element_type = match item.pop() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => group,
+            _ => {
+                let msg = "expected a static or const with the element type in brackets, \
+                           as in `static TABLE: [fn()] = N in 0..4 => f#N`";
+                return Err(input.error(msg));
+            }
+        };
+        input.parse::<Token![=]>()?;
+        let header = single(input)?;
+        input.parse::<Token![=>]>()?;
+        let element = until(input, |input| input.peek(Token![;]))?;
+        input.parse::<Option<Token![;]>>()?;
+        Ok(Table {
+            item: item.into_iter().collect(),
+            element_type,
+            header,
+            element,
+        })
+    }
+}
+
+// The header of dispatch! and table!, which iterate a single variable.
+fn single(input: ParseStream) -> Result<Header> {
+    let header = parse::header(input)?;
+    if let Some(var) = header.vars.get(1) {
+        let msg = "only one loop variable is supported here";
+        return Err(Error::new(var.idents[0].span(), msg));
+    }
+    Ok(header)
+}
+
+fn until(input: ParseStream, end: impl Fn(ParseStream) -> bool) -> Result<TokenStream> {
+    let mut tokens = TokenStream::new();
+    while !input.is_empty() && !end(input) {
+        tokens.extend([input.parse::<TokenTree>()?]);
+    }
+    Ok(tokens)
+}
+
+// Every element of the body is a #(...)* section iterating the variable.
+fn section(body: TokenStream) -> TokenStream {
+    let pound = Punct::new('#', Spacing::Alone);
+    let group = Group::new(Delimiter::Parenthesis, body);
+    let star = Punct::new('*', Spacing::Alone);
+    quote!(#pound #group #star)
+}
+
+// A match with one arm per value, which matches the value of the variable or
+// of the index of `(I, T) in enumerate [...]`.
+pub fn dispatch(input: Dispatch) -> Result<TokenStream> {
+    let Dispatch {
+        scrutinee,
+        header,
+        arm,
+        fallback,
+    } = input;
+    let pattern = &header.vars[0].idents[0];
+    let pound = Punct::new('#', Spacing::Alone);
+    let arms = section(quote!(#pound #pattern => #arm,));
+    let arms = expand::expand(&Seq { header, body: arms })?;
+    let fallback = match fallback {
+        Some(fallback) => quote!(#fallback),
+        None => quote!(::core::panic!("no arm of seq::dispatch! matches the value")),
+    };
+    Ok(quote! {
+        match #scrutinee {
+            #arms
+            #[allow(unreachable_patterns)]
+            _ => #fallback,
+        }
+    })
+}
+
+// The item with the length of the array filled in and one element per value.
+pub fn table(input: Table) -> Result<TokenStream> {
+    let Table {
+        item,
+        element_type,
+        header,
+        element,
+    } = input;
+    let len = header.vars[0].values.len();
+    let elements = section(quote!(#element,));
+    let elements = expand::expand(&Seq {
+        header,
+        body: elements,
+    })?;
+    let len = Literal::usize_unsuffixed(len);
+    let ty = element_type.stream();
+    let mut array_type = Group::new(Delimiter::Bracket, quote!(#ty; #len));
+    array_type.set_span(element_type.span());
+    Ok(quote! {
+        #item #array_type = [#elements];
+    })
+}
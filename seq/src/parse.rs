@@ -0,0 +1,351 @@
+use crate::range;
+use proc_macro2::{Delimiter, Group, Literal, Span, TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::{braced, bracketed, parenthesized, token, Error, Ident, LitInt, Result, Token};
+
+mod kw {
+    syn::custom_keyword!(enumerate);
+    syn::custom_keyword!(limit);
+}
+
+// The default of `limit = ...` in the header. A table of 65536 elements is
+// fine, anything larger has to opt in.
+const DEFAULT_LIMIT: usize = 1 << 16;
+
+pub fn limit_exceeded(tokens: impl ToTokens, limit: usize) -> Error {
+    let msg = format!(
+        "this expands to more than {} iterations; consider generating a table \
+         this large with a const fn or a build script, or raise the limit with \
+         `limit = ...` after the loop variables",
+        limit,
+    );
+    Error::new_spanned(tokens, msg)
+}
+
+// seq!(N in 0..4, M in 0..8 { ... })
+pub struct Seq {
+    pub header: Header,
+    pub body: TokenStream,
+}
+
+// N in 0..4, M in 0..8, limit = 100_000
+//
+// This is also all of #[seq::repeat(...)].
+pub struct Header {
+    pub vars: Vec<Var>,
+    // The most iterations that the expansion may take in total, counting
+    // every iteration of a nested loop.
+    pub limit: usize,
+}
+
+// One loop of the header. Usually that is a single variable, but
+// `(I, T) in enumerate [...]` binds two at once, so every element of `values`
+// holds one value for each of `idents`.
+pub struct Var {
+    pub idents: Vec<Ident>,
+    pub values: Vec<Vec<Value>>,
+    // The integer type of `N: u8 in 0..16`, which is the suffix of the
+    // literals that the variable turns into.
+    pub ty: Option<Ident>,
+}
+
+#[derive(Clone)]
+pub enum Value {
+    Int(i128),
+    // Only for integers that do not fit in i128.
+    UInt(u128),
+    Char(char),
+    // An element of a list like `[u8, u16, u32]`.
+    Tokens(TokenStream),
+}
+
+impl Value {
+    // The value as written into an identifier by `f#N`. Only a single
+    // identifier or literal can be pasted out of a list element.
+    pub fn to_ident_fragment(&self, span: Span) -> Result<String> {
+        match self {
+            Value::Int(value) => Ok(value.to_string()),
+            Value::UInt(value) => Ok(value.to_string()),
+            Value::Char(value) => Ok(value.to_string()),
+            Value::Tokens(tokens) => {
+                let mut iter = tokens.clone().into_iter();
+                match (iter.next(), iter.next()) {
+                    (Some(TokenTree::Ident(ident)), None) => Ok(ident.unraw().to_string()),
+                    (Some(TokenTree::Literal(literal)), None) => Ok(literal.to_string()),
+                    _ => {
+                        let msg = format!("cannot paste `{}` into an identifier", tokens);
+                        Err(Error::new(span, msg))
+                    }
+                }
+            }
+        }
+    }
+
+    // The value as written into a string literal by "#N".
+    pub fn to_text(&self) -> String {
+        match self {
+            Value::Int(value) => value.to_string(),
+            Value::UInt(value) => value.to_string(),
+            Value::Char(value) => value.to_string(),
+            Value::Tokens(tokens) => {
+                let mut iter = tokens.clone().into_iter();
+                match (iter.next(), iter.next()) {
+                    (Some(TokenTree::Ident(ident)), None) => ident.unraw().to_string(),
+                    _ => tokens.to_string(),
+                }
+            }
+        }
+    }
+
+    // The value in place of an occurrence of the loop variable at `span`.
+    // Numbers and characters become literals with that span, while list
+    // elements keep the spans they were written with.
+    pub fn to_tokens(&self, span: Span) -> TokenStream {
+        let mut literal = match self {
+            Value::Int(value) => Literal::i128_unsuffixed(*value),
+            Value::UInt(value) => Literal::u128_unsuffixed(*value),
+            Value::Char(value) => Literal::character(*value),
+            Value::Tokens(tokens) => return tokens.clone(),
+        };
+        literal.set_span(span);
+        self.atomic(literal)
+    }
+
+    // Like `to_tokens` but with a suffix, as in `3u8`. Fails unless the value
+    // is an integer in the range of `ty`.
+    pub fn to_suffixed_tokens(&self, span: Span, ty: &Ident) -> Result<TokenStream> {
+        let (min, max) = int_bounds(ty).expect("integer type");
+        let fits = match self {
+            Value::Int(value) => *value >= min && (*value < 0 || *value as u128 <= max),
+            Value::UInt(value) => *value <= max,
+            Value::Char(_) | Value::Tokens(_) => {
+                let msg = format!("`{}` is not an integer", self.to_text());
+                return Err(Error::new(span, msg));
+            }
+        };
+        if !fits {
+            let msg = format!("`{}` does not fit in `{}`", self.to_text(), ty);
+            return Err(Error::new(span, msg));
+        }
+        let repr = format!("{}{}", self.to_text(), ty);
+        let mut literal: Literal = repr.parse().expect("suffixed integer literal");
+        literal.set_span(span);
+        Ok(self.atomic(literal))
+    }
+
+    // A negative literal is wrapped in parentheses, so that it stays one
+    // operand in `N.abs()` instead of turning into `-(3.abs())`.
+    fn atomic(&self, literal: Literal) -> TokenStream {
+        let span = literal.span();
+        let literal = TokenTree::Literal(literal);
+        match self {
+            Value::Int(value) if *value < 0 => {
+                let mut group = Group::new(Delimiter::Parenthesis, literal.into());
+                group.set_span(span);
+                TokenTree::Group(group).into()
+            }
+            _ => literal.into(),
+        }
+    }
+}
+
+// The smallest and largest value of an integer type, or None if `ty` is not
+// one. The pointer-sized types are assumed to have 64 bits; a literal out of
+// range on a smaller target is still caught by the compiler.
+pub fn int_bounds(ty: &Ident) -> Option<(i128, u128)> {
+    let bounds = match ty.to_string().as_str() {
+        "u8" => (0, u8::MAX as u128),
+        "u16" => (0, u16::MAX as u128),
+        "u32" => (0, u32::MAX as u128),
+        "u64" | "usize" => (0, u64::MAX as u128),
+        "u128" => (0, u128::MAX),
+        "i8" => (i8::MIN as i128, i8::MAX as u128),
+        "i16" => (i16::MIN as i128, i16::MAX as u128),
+        "i32" => (i32::MIN as i128, i32::MAX as u128),
+        "i64" | "isize" => (i64::MIN as i128, i64::MAX as u128),
+        "i128" => (i128::MIN, i128::MAX as u128),
+        _ => return None,
+    };
+    Some(bounds)
+}
+
+impl Parse for Seq {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let header = header(input)?;
+        if !input.peek(token::Brace) {
+            let msg = "expected the body in braces after the loop variables, as in \
+                       `seq!(N in 0..4 { ... })`";
+            return Err(input.error(msg));
+        }
+        let content;
+        braced!(content in input);
+        let body: TokenStream = content.parse()?;
+        Ok(Seq { header, body })
+    }
+}
+
+impl Parse for Header {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let header = header(input)?;
+        if !input.is_empty() {
+            return Err(input.error("expected `,` or the end of the loop variables"));
+        }
+        Ok(header)
+    }
+}
+
+pub fn header(input: ParseStream) -> Result<Header> {
+    let mut vars = Vec::new();
+    let mut limit = None;
+    let range_limit = find_limit(input).unwrap_or(DEFAULT_LIMIT);
+    loop {
+        if input.peek(kw::limit) && input.peek2(Token![=]) {
+            let keyword = input.parse::<kw::limit>()?;
+            input.parse::<Token![=]>()?;
+            if limit.is_some() {
+                return Err(Error::new(keyword.span, "duplicate `limit`"));
+            }
+            limit = Some(input.parse::<LitInt>()?.base10_parse()?);
+            if input.peek(Token![,]) {
+                input.parse::<Token![,]>()?;
+                continue;
+            }
+            break;
+        }
+        let var = Var::parse(input, range_limit)?;
+        for ident in &var.idents {
+            let prev = vars.iter().flat_map(|prev: &Var| &prev.idents);
+            if prev
+                .chain(&var.idents)
+                .filter(|prev| *prev == ident)
+                .count()
+                > 1
+            {
+                let msg = format!("duplicate loop variable `{}`", ident);
+                return Err(Error::new(ident.span(), msg));
+            }
+        }
+        vars.push(var);
+        if input.peek(Token![,]) {
+            input.parse::<Token![,]>()?;
+        } else {
+            break;
+        }
+    }
+    if vars.is_empty() {
+        return Err(input.error("expected a loop variable like `N in 0..4`"));
+    }
+    Ok(Header {
+        vars,
+        limit: limit.unwrap_or(DEFAULT_LIMIT),
+    })
+}
+
+// The `limit = ...` of the header, which may come after the loop variables.
+// Every range is checked against it before its values are collected, so that
+// a huge range fails with an error instead of running out of memory.
+fn find_limit(input: ParseStream) -> Option<usize> {
+    let fork = input.fork();
+    while !fork.is_empty() && !fork.peek(token::Brace) && !fork.peek(Token![=>]) {
+        if fork.peek(kw::limit) && fork.peek2(Token![=]) {
+            fork.parse::<kw::limit>().ok()?;
+            fork.parse::<Token![=]>().ok()?;
+            return fork.parse::<LitInt>().ok()?.base10_parse().ok();
+        }
+        fork.parse::<TokenTree>().ok()?;
+    }
+    None
+}
+
+impl Var {
+    fn parse(input: ParseStream, limit: usize) -> Result<Self> {
+        let enumerated = input.peek(token::Paren);
+        let idents = if enumerated {
+            let content;
+            parenthesized!(content in input);
+            let index: Ident = content.parse()?;
+            content.parse::<Token![,]>()?;
+            let ident: Ident = content.parse()?;
+            content.parse::<Option<Token![,]>>()?;
+            vec![index, ident]
+        } else if input.peek(Ident) {
+            vec![input.parse()?]
+        } else {
+            return Err(input.error("expected a loop variable, as in `N in 0..4`"));
+        };
+        let ty = if !enumerated && input.peek(Token![:]) {
+            input.parse::<Token![:]>()?;
+            let ty: Ident = input.parse()?;
+            if int_bounds(&ty).is_none() {
+                let msg = "expected an integer type like `u8` or `usize`";
+                return Err(Error::new(ty.span(), msg));
+            }
+            Some(ty)
+        } else {
+            None
+        };
+        if !input.peek(Token![in]) {
+            let msg = format!(
+                "expected `in` after the loop variable, as in `{} in 0..4`",
+                idents[idents.len() - 1],
+            );
+            return Err(input.error(msg));
+        }
+        input.parse::<Token![in]>()?;
+        if enumerated {
+            input.parse::<kw::enumerate>()?;
+        } else if input.peek(kw::enumerate) {
+            let msg = "`enumerate` binds two loop variables, as in `(I, T) in enumerate [...]`";
+            return Err(input.error(msg));
+        }
+
+        let values = if input.peek(token::Bracket) {
+            list(input)?
+        } else {
+            range::parse(input, limit)?
+        };
+        if let Some(ty) = &ty {
+            for value in &values {
+                value.to_suffixed_tokens(ty.span(), ty)?;
+            }
+        }
+        let values = if enumerated {
+            values
+                .into_iter()
+                .enumerate()
+                .map(|(i, value)| vec![Value::Int(i as i128), value])
+                .collect()
+        } else {
+            values.into_iter().map(|value| vec![value]).collect()
+        };
+        Ok(Var { idents, values, ty })
+    }
+}
+
+// `[u8, u16, Vec<u8>]`, split at the commas outside of any group. An element
+// that itself contains a comma has to be wrapped in braces, which are removed.
+fn list(input: ParseStream) -> Result<Vec<Value>> {
+    let content;
+    bracketed!(content in input);
+    let mut values = Vec::new();
+    while !content.is_empty() {
+        let mut element = TokenStream::new();
+        while !content.is_empty() && !content.peek(Token![,]) {
+            element.extend([content.parse::<TokenTree>()?]);
+        }
+        if element.is_empty() {
+            return Err(content.error("expected a list element before `,`"));
+        }
+        let mut iter = element.clone().into_iter();
+        if let (Some(TokenTree::Group(group)), None) = (iter.next(), iter.next()) {
+            if group.delimiter() == Delimiter::Brace {
+                element = group.stream();
+            }
+        }
+        values.push(Value::Tokens(element));
+        content.parse::<Option<Token![,]>>()?;
+    }
+    Ok(values)
+}
@@ -0,0 +1,219 @@
+use crate::parse::{self, Value};
+use proc_macro2::{Delimiter, Punct, Spacing, Span, TokenStream, TokenTree};
+use std::convert::TryFrom;
+use syn::parse::{Parse, ParseStream};
+use syn::{parenthesized, token, Error, LitChar, LitInt, Result, Token};
+
+mod kw {
+    syn::custom_keyword!(step);
+    syn::custom_keyword!(rev);
+}
+
+// The values of a range like `0..16`, `0..=64 step 8`, `-4..4`, `'a'..='z'`
+// or `(0..16).rev()`, which may be at most `limit` many.
+pub fn parse(input: ParseStream, limit: usize) -> Result<Vec<Value>> {
+    if input.peek(token::Paren) {
+        let content;
+        parenthesized!(content in input);
+        let mut values = parse(&content, limit)?;
+        if !content.is_empty() {
+            return Err(content.error("unexpected token after range"));
+        }
+        input.parse::<Token![.]>()?;
+        input.parse::<kw::rev>()?;
+        let args;
+        parenthesized!(args in input);
+        if !args.is_empty() {
+            return Err(args.error("`rev` takes no arguments"));
+        }
+        values.reverse();
+        return Ok(values);
+    }
+
+    let start: Bound = input.parse()?;
+    let inclusive = if input.peek(Token![..=]) {
+        input.parse::<Token![..=]>()?;
+        true
+    } else {
+        input.parse::<Token![..]>()?;
+        false
+    };
+    let end: Bound = input.parse()?;
+    let step = if input.peek(kw::step) {
+        input.parse::<kw::step>()?;
+        let lit: LitInt = input.parse()?;
+        let step: usize = lit.base10_parse()?;
+        if step == 0 {
+            return Err(Error::new(lit.span(), "step must be greater than zero"));
+        }
+        step
+    } else {
+        1
+    };
+    let too_long = |len: Option<u128>| match len {
+        Some(len) if len <= limit as u128 => Ok(()),
+        _ => {
+            // Spans from the start to the end of the range.
+            let range = [start.span, end.span]
+                .iter()
+                .map(|&span| {
+                    let mut punct = Punct::new('.', Spacing::Alone);
+                    punct.set_span(span);
+                    TokenTree::Punct(punct)
+                })
+                .collect::<TokenStream>();
+            Err(parse::limit_exceeded(range, limit))
+        }
+    };
+
+    let values = match (start.kind, end.kind) {
+        (Kind::Signed(lo), Kind::Signed(hi)) => {
+            check_order(&start, lo > hi, &end)?;
+            too_long(len((hi as u128).wrapping_sub(lo as u128), inclusive, step))?;
+            if inclusive {
+                (lo..=hi).step_by(step).map(Value::Int).collect()
+            } else {
+                (lo..hi).step_by(step).map(Value::Int).collect()
+            }
+        }
+        (Kind::Char(lo), Kind::Char(hi)) => {
+            check_order(&start, lo > hi, &end)?;
+            too_long(len(chars_between(lo, hi), inclusive, step))?;
+            if inclusive {
+                (lo..=hi).step_by(step).map(Value::Char).collect()
+            } else {
+                (lo..hi).step_by(step).map(Value::Char).collect()
+            }
+        }
+        (Kind::Char(_), _) | (_, Kind::Char(_)) => {
+            let msg = "both ends of a range need to be characters, or both integers";
+            return Err(Error::new(end.span, msg));
+        }
+        (lo, hi) => {
+            // At least one end is too large for i128.
+            let lo = unsigned(lo, start.span)?;
+            let hi = unsigned(hi, end.span)?;
+            check_order(&start, lo > hi, &end)?;
+            too_long(len(hi - lo, inclusive, step))?;
+            if inclusive {
+                (lo..=hi).step_by(step).map(Value::UInt).collect()
+            } else {
+                (lo..hi).step_by(step).map(Value::UInt).collect()
+            }
+        }
+    };
+    Ok(values)
+}
+
+// The number of values of a range whose end is `distance` values after its
+// start, or None if that does not even fit in u128.
+fn len(distance: u128, inclusive: bool, step: usize) -> Option<u128> {
+    let step = step as u128;
+    if inclusive {
+        (distance / step).checked_add(1)
+    } else {
+        Some(distance.div_ceil(step))
+    }
+}
+
+// Like `hi - lo` for characters, not counting the surrogates in between, which
+// a range of characters skips.
+fn chars_between(lo: char, hi: char) -> u128 {
+    let surrogates = if lo <= '\u{d7ff}' && hi >= '\u{e000}' {
+        0x800
+    } else {
+        0
+    };
+    (hi as u128) - (lo as u128) - surrogates
+}
+
+struct Bound {
+    kind: Kind,
+    span: Span,
+}
+
+#[derive(Copy, Clone)]
+enum Kind {
+    Signed(i128),
+    Unsigned(u128),
+    Char(char),
+}
+
+impl Parse for Bound {
+    fn parse(input: ParseStream) -> Result<Self> {
+        if input.peek(LitChar) {
+            let lit: LitChar = input.parse()?;
+            return Ok(Bound {
+                kind: Kind::Char(lit.value()),
+                span: lit.span(),
+            });
+        }
+        let neg: Option<Token![-]> = input.parse()?;
+        if !input.peek(LitInt) {
+            return Err(not_literal(input));
+        }
+        let lit: LitInt = input.parse()?;
+        let magnitude: u128 = lit.base10_parse()?;
+        let kind = if neg.is_some() {
+            match 0i128.checked_sub_unsigned(magnitude) {
+                Some(value) => Kind::Signed(value),
+                None => {
+                    let msg = "integer is too small to be the bound of a range";
+                    return Err(Error::new(lit.span(), msg));
+                }
+            }
+        } else {
+            match i128::try_from(magnitude) {
+                Ok(value) => Kind::Signed(value),
+                Err(_) => Kind::Unsigned(magnitude),
+            }
+        };
+        Ok(Bound {
+            kind,
+            span: lit.span(),
+        })
+    }
+}
+
+// Most likely the bound is a constant, which seq! cannot know the value of
+// because macros expand before constants are evaluated.
+fn not_literal(input: ParseStream) -> Error {
+    let found = match input.fork().parse::<TokenTree>() {
+        Ok(TokenTree::Ident(ident)) => ident,
+        Ok(TokenTree::Group(group)) if group.delimiter() == Delimiter::None => {
+            match group.stream().into_iter().next() {
+                Some(TokenTree::Ident(ident)) => ident,
+                _ => return input.error("expected an integer or character literal"),
+            }
+        }
+        _ => return input.error("expected an integer or character literal"),
+    };
+    let msg = format!(
+        "expected an integer or character literal, found `{}`; macros expand before \
+         constants get a value, so to share a bound, keep it in a macro_rules macro \
+         that passes it to seq! as a `$n:literal`",
+        found,
+    );
+    Error::new(found.span(), msg)
+}
+
+fn unsigned(kind: Kind, span: Span) -> Result<u128> {
+    match kind {
+        Kind::Signed(value) => u128::try_from(value).map_err(|_| {
+            let msg = "a range cannot mix negative bounds with bounds that only fit in u128";
+            Error::new(span, msg)
+        }),
+        Kind::Unsigned(value) => Ok(value),
+        Kind::Char(_) => unreachable!(),
+    }
+}
+
+fn check_order(start: &Bound, reversed: bool, end: &Bound) -> Result<()> {
+    if reversed {
+        let msg =
+            "the start of the range is after its end; to count down, write `(end..start).rev()`";
+        let span = start.span.join(end.span).unwrap_or(start.span);
+        return Err(Error::new(span, msg));
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+// Register banks and other grids are indexed by more than one number. Accept
+// several loop variables separated by commas:
+//
+//     seq!(N in 0..4, M in 0..8 {
+//         ...
+//     });
+//
+// Without any #(...)* sections, the body is repeated for every combination of
+// values, with the first variable changing slowest, like nested for loops.
+// Identifiers can paste in any number of variables, as in `reg_#N#_#M` which
+// becomes reg_0_0, reg_0_1, and so on.
+//
+// When the body does contain #(...)* sections, each one repeats over the next
+// variable in order: a section in the body repeats over N, and a section
+// nested inside of that one repeats over M.
+
+use seq::seq;
+
+seq!(N in 0..2, M in 0..3 {
+    const REG_#N#_#M: (u8, u8) = (N, M);
+});
+
+seq!(N in 0..2, M in 0..3 {
+    const GRID: [[(u8, u8); 3]; 2] = [
+        #(
+            [#((N, M),)*],
+        )*
+    ];
+});
+
+seq!(B in 0..3, R in 0..4 {
+    #[derive(Default)]
+    struct Banks {
+        #(
+            bank#B: Bank#B,
+        )*
+    }
+
+    #(
+        #[derive(Default)]
+        struct Bank#B {
+            #(
+                reg#R: u32,
+            )*
+        }
+
+        impl Bank#B {
+            fn sum(&self) -> u32 {
+                0 #(+ self.reg#R)*
+            }
+        }
+    )*
+});
+
+fn main() {
+    assert_eq!(REG_0_0, (0, 0));
+    assert_eq!(REG_0_2, (0, 2));
+    assert_eq!(REG_1_1, (1, 1));
+
+    assert_eq!(GRID[1][2], (1, 2));
+    assert_eq!(GRID[0][1], (0, 1));
+
+    let mut banks = Banks::default();
+    banks.bank2.reg3 = 5;
+    banks.bank2.reg0 = 1;
+    assert_eq!(banks.bank0.sum(), 0);
+    assert_eq!(banks.bank2.sum(), 6);
+    let _ = (banks.bank1.reg1, banks.bank2.reg2);
+}
@@ -0,0 +1,68 @@
+// Besides `a..b` and `a..=b`, support ranges that:
+//
+//   - skip values, with `0..64 step 8`;
+//
+//   - count down, written like the equivalent Rust iterator `(0..16).rev()`.
+//     A step can be combined with rev, as in `(0..64 step 8).rev()`;
+//
+//   - have negative bounds, or bounds anywhere in the range of i128 and u128;
+//
+//   - iterate over characters, like `'a'..='z'`. The loop variable expands to
+//     a char literal, and `#C` pastes the character itself into an
+//     identifier.
+
+use seq::seq;
+
+seq!(N in 0..64 step 16 {
+    const OFFSET_#N: u32 = N;
+});
+
+seq!(N in (0..4).rev() {
+    const COUNTDOWN: [u8; 4] = [#(N,)*];
+});
+
+seq!(N in (0..=64 step 16).rev() {
+    const OFFSETS_DOWN: [u32; 5] = [#(N,)*];
+});
+
+seq!(N in -2..=2 {
+    const AROUND_ZERO: [i32; 5] = [#(N,)*];
+});
+
+seq!(N in 170141183460469231731687303715884105727..=170141183460469231731687303715884105729 {
+    const HUGE: [u128; 3] = [#(N,)*];
+});
+
+seq!(C in 'a'..='e' {
+    #[derive(Debug, PartialEq)]
+    enum Letter {
+        #(
+            Letter#C,
+        )*
+    }
+
+    impl Letter {
+        fn to_char(&self) -> char {
+            match self {
+                #(
+                    Letter::Letter#C => C,
+                )*
+            }
+        }
+    }
+});
+
+seq!(C in 'a'..'z' step 12 {
+    const EVERY_TWELFTH: [char; 3] = [#(C,)*];
+});
+
+fn main() {
+    assert_eq!(OFFSET_0 + OFFSET_16 + OFFSET_32 + OFFSET_48, 96);
+    assert_eq!(COUNTDOWN, [3, 2, 1, 0]);
+    assert_eq!(OFFSETS_DOWN, [64, 48, 32, 16, 0]);
+    assert_eq!(AROUND_ZERO, [-2, -1, 0, 1, 2]);
+    assert_eq!(HUGE[2], i128::MAX as u128 + 2);
+    assert_eq!(Letter::Letterc.to_char(), 'c');
+    assert_eq!(Letter::Lettere.to_char(), 'e');
+    assert_eq!(EVERY_TWELFTH, ['a', 'm', 'y']);
+}
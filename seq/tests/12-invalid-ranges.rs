@@ -0,0 +1,33 @@
+// Ranges that can't be iterated are reported at the part of the range that is
+// wrong. A range with more values than the limit is reported at the range,
+// before any of its values are generated, so that even a range of billions
+// of values fails quickly. Nested loops that take more iterations in total
+// than the limit are reported at the loop variable that crosses it.
+
+use seq::seq;
+
+seq!(N in 16..0 {});
+
+seq!(N in 0..64 step 0 {});
+
+seq!(N in 'a'..10 {});
+
+seq!(N in -1..340282366920938463463374607431768211455 {});
+
+seq!(N in (0..4).reverse() {});
+
+seq!(C in '*'..='+' {
+    fn op_#C() {}
+});
+
+seq!(N in 0..70000 {});
+
+seq!(N in 0..4294967296 {});
+
+seq!(N in (0..=4294967295).rev(), limit = 100_000 {});
+
+seq!(N in 0..256, M in 0..256, limit = 65791 {
+    #(#(const _: u16 = N * M;)*)*
+});
+
+fn main() {}
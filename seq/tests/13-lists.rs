@@ -0,0 +1,77 @@
+// Much repetitive code iterates over a fixed set of names rather than over
+// numbers, like implementing a trait for each of the integer types. Besides
+// ranges, accept a list of elements in square brackets:
+//
+//     seq!(T in [u8, u16, u32, u64] {
+//         impl Foo for T {}
+//     });
+//
+// Each element is any sequence of tokens up to the next comma at the top
+// level; an element that contains a comma of its own can be wrapped in
+// braces, which are not part of the element. The loop variable is replaced by the element's tokens as they
+// were written. Elements that are a single identifier or literal can also be
+// pasted into identifiers, as in `is_#T`.
+//
+// To get at the position of each element too, bind a second variable with
+// `enumerate`:
+//
+//     seq!((I, T) in enumerate [u8, u16] {
+//         ...
+//     });
+
+use seq::seq;
+
+trait Bits {
+    const BITS: u32;
+}
+
+seq!(T in [u8, u16, u32, u64] {
+    impl Bits for T {
+        const BITS: u32 = T::MAX.count_ones();
+    }
+});
+
+seq!(T in [Vec<u8>, {std::collections::HashMap<u8, u8>}, [u8; 2]] {
+    fn lengths() -> [usize; 3] {
+        [#(<T>::default().len(),)*]
+    }
+});
+
+seq!((I, T) in enumerate [red, green, blue] {
+    #[allow(non_camel_case_types)]
+    #[derive(Debug, PartialEq)]
+    enum Color {
+        #(
+            T = I,
+        )*
+    }
+
+    impl Color {
+        #(
+            const fn is_#T(&self) -> bool {
+                matches!(self, Color::T)
+            }
+        )*
+    }
+});
+
+seq!((I, N) in enumerate (10..40 step 10).rev() {
+    const INDEXED: [(usize, u32); 3] = [#((I, N),)*];
+});
+
+#[allow(non_camel_case_types)]
+struct red;
+
+fn main() {
+    assert_eq!(<u16 as Bits>::BITS, 16);
+    assert_eq!(<u64 as Bits>::BITS, 64);
+
+    assert_eq!(lengths(), [0, 0, 2]);
+
+    assert_eq!(Color::green as u8, 1);
+    assert!(Color::blue.is_blue());
+    assert!(!Color::red.is_green());
+    let _ = red;
+
+    assert_eq!(INDEXED, [(0, 30), (1, 20), (2, 10)]);
+}
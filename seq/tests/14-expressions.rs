@@ -0,0 +1,98 @@
+// Register maps need offsets and masks computed from the loop variable. Inside
+// the body, #(...) with an expression in it evaluates integer arithmetic on
+// the loop variables: `#(N * 4)`, `#(N + 1)`, `#(1 << N)`, and so on with
+// + - * / % << >> & | ^ and unary - and !. Unlike a #(...)* section it is not
+// followed by `*`.
+//
+// The result can be used as a literal, or pasted into an identifier as in
+// `slot_#(N + 1)`.
+//
+// A format spec controls how the number is written: `Reg#N:02` pastes Reg00
+// through Reg15, and `irq_#N:x` pastes the hex digits. The same spec can go
+// inside an expression, `#(N * 16:04x)`, which also works as a literal with
+// the prefix of its radix, like 0x0010. After a pasted variable, the spec is
+// written without spaces around its `:`, and only specs starting with a digit
+// or consisting of `x`, `b` or `o` are recognized. That way `field#N: 7` in a
+// struct literal is still a field and its value.
+
+use seq::seq;
+
+seq!(N in 0..16 {
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    enum Reg {
+        #(
+            Reg#N:02 = #(N * 4),
+        )*
+    }
+
+    const MASKS: [u32; 16] = [#(#(1 << N),)*];
+});
+
+seq!(N in 8..12 {
+    #(
+        fn irq_#N:x() -> u16 {
+            #(N * 16:04x)
+        }
+    )*
+});
+
+seq!(N in 0..3 {
+    struct Slots {
+        #(
+            slot_#(N + 1): u8,
+        )*
+    }
+});
+
+seq!(N in 0..2 {
+    #(
+        const NEG_#N: i32 = #(-N - 1);
+    )*
+});
+
+seq!(N in 0..2 {
+    struct Regs {
+        #(
+            reg#N: u32,
+        )*
+    }
+
+    fn regs() -> Regs {
+        let reg0 = 99;
+        let _ = reg0;
+        Regs {
+            #(
+                reg#N: 0,
+            )*
+        }
+    }
+
+    fn sevens() -> Regs {
+        Regs { #(reg#N: 7,)* }
+    }
+});
+
+fn main() {
+    assert_eq!(Reg::Reg00 as u32, 0);
+    assert_eq!(Reg::Reg07 as u32, 28);
+    assert_eq!(Reg::Reg15 as u32, 60);
+    assert_eq!(MASKS[4], 16);
+
+    assert_eq!(irq_8(), 128);
+    assert_eq!(irq_a(), 160);
+    assert_eq!(irq_b(), 0xb0);
+
+    let slots = Slots {
+        slot_1: 1,
+        slot_2: 2,
+        slot_3: 3,
+    };
+    assert_eq!(slots.slot_1 + slots.slot_2 + slots.slot_3, 6);
+
+    assert_eq!(NEG_0 + NEG_1, -3);
+
+    let regs = regs();
+    assert_eq!((regs.reg0, regs.reg1), (0, 0));
+    let regs = sevens();
+    assert_eq!((regs.reg0, regs.reg1), (7, 7));
+}
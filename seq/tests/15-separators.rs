@@ -0,0 +1,44 @@
+// Like in macro_rules, a repetition section can have a separator between the
+// `)` and the `*`: #(...),* or #(...);* or #(...)|* or any other single
+// punctuation character. The separator is placed between the repetitions but
+// not after the last one, for places where Rust does not allow a trailing
+// separator, like match alternatives and arithmetic.
+
+use seq::seq;
+
+seq!(N in 0..4 {
+    #[derive(Copy, Clone, PartialEq, Debug)]
+    enum Interrupt {
+        #(Irq#N),*
+    }
+
+    fn is_low(interrupt: Interrupt) -> bool {
+        matches!(interrupt, #(Interrupt::Irq#N)|*)
+    }
+
+    fn sum(#(x#N: u32),*) -> u32 {
+        #(x#N)+*
+    }
+});
+
+seq!(N in 0..2 {
+    type Pair = (#(u#(8 << N)),*);
+});
+
+seq!(N in 0..3 {
+    fn squares() -> [u32; 3] {
+        let mut squares = [0; 3];
+        #(squares[N] = N * N);*;
+        squares
+    }
+});
+
+fn main() {
+    assert!(is_low(Interrupt::Irq3));
+    assert_eq!(sum(1, 2, 3, 4), 10);
+
+    let pair: Pair = (1, 2);
+    assert_eq!(pair, (1u8, 2u16));
+
+    assert_eq!(squares(), [0, 1, 4]);
+}
@@ -0,0 +1,82 @@
+// Generated tables often have exceptions, like a reserved interrupt line or an
+// attribute on the last element only. Support conditionals in the body that
+// are evaluated during expansion:
+//
+//     #if N % 2 == 0 {
+//         ...
+//     } #else #if N == 3 {
+//         ...
+//     } #else {
+//         ...
+//     }
+//
+// Conditions compare integer expressions with == != < <= > >= and combine
+// them with && || and !. Characters compare too, and list elements can be
+// tested for equality, as in `#if T == u8`.
+//
+// Also provide the built-in variables #FIRST and #LAST, the first and last
+// value of the innermost loop variable, and #COUNT, the number of iterations
+// of the innermost loop. In a condition or #(...) expression they can be
+// written without the `#`.
+
+use seq::seq;
+
+seq!(N in 0..6 {
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    enum Irq {
+        #(
+            #if N != 2 {
+                Irq#N = N,
+            }
+        )*
+    }
+
+    const KINDS: [&str; #COUNT] = [
+        #(
+            #if N == FIRST {
+                "first"
+            } #else #if N == #LAST {
+                "last"
+            } #else #if N % 2 == 0 && !(N > 3) {
+                "even"
+            } #else {
+                "other"
+            },
+        )*
+    ];
+
+    const SPAN: u32 = #(LAST - FIRST);
+});
+
+seq!(C in 'a'..='d' {
+    const VOWELS: [char; 1] = [#(#if C == 'a' || C == 'e' { C, })*];
+});
+
+seq!(T in [u8, u16, bool] {
+    trait Zero {
+        const ZERO: Self;
+    }
+
+    #(
+        impl Zero for T {
+            #if T == bool {
+                const ZERO: Self = false;
+            } #else {
+                const ZERO: Self = 0;
+            }
+        }
+    )*
+});
+
+fn main() {
+    assert_eq!(Irq::Irq3 as u8, 3);
+    assert_eq!(Irq::Irq5 as u8, 5);
+
+    assert_eq!(KINDS, ["first", "other", "even", "other", "other", "last"]);
+    assert_eq!(SPAN, 5);
+
+    assert_eq!(VOWELS, ['a']);
+
+    assert_eq!(<u16 as Zero>::ZERO, 0);
+    assert!(!<bool as Zero>::ZERO);
+}
@@ -0,0 +1,70 @@
+// Generated items deserve accurate documentation. Substitute loop variables
+// inside string literals, including the ones that doc comments and
+// `#[doc = "..."]` attributes are made of:
+//
+//     /// Interrupt line #N
+//     fn irq#N() -> &'static str { "irq#N" }
+//
+// A `#` that is not followed by a loop variable stays as it is.
+//
+// When iterating over a list of identifiers it is often necessary to change
+// their case, for example to derive `fn read_u16` from a type name `U16`.
+// A loop variable can be followed by a case modifier, either where it is
+// pasted into an identifier or on its own, and inside strings too:
+//
+//     #T:snake   u16, http_request
+//     #T:camel   u16, httpRequest
+//     #T:pascal  U16, HttpRequest
+//     #T:upper   U16, HTTP_REQUEST
+
+use seq::seq;
+
+seq!(N in 0..3 {
+    #(
+        /// Interrupt line #N
+        #[doc = "Handles irq#N, not #M or # N."]
+        fn irq#N() -> &'static str {
+            "irq#N"
+        }
+    )*
+});
+
+struct U16;
+struct HttpRequest;
+
+seq!(T in [U16, HttpRequest] {
+    #(
+        fn read_#T:snake() -> &'static str {
+            "#T:snake #T:camel #T:pascal #T:upper #T:other"
+        }
+
+        const NAME_#T:upper: &str = stringify!(#T:pascal);
+    )*
+});
+
+seq!(T in [read_byte, writeByte] {
+    #(
+        struct #T:pascal;
+
+        fn camel_#T:snake() -> &'static str {
+            stringify!(#T:camel)
+        }
+    )*
+});
+
+fn main() {
+    assert_eq!(irq0(), "irq0");
+    assert_eq!(irq2(), "irq2");
+
+    assert_eq!(read_u16(), "u16 u16 U16 U16 U16:other");
+    assert_eq!(
+        read_http_request(),
+        "http_request httpRequest HttpRequest HTTP_REQUEST HttpRequest:other",
+    );
+    assert_eq!(NAME_U16, "U16");
+    assert_eq!(NAME_HTTP_REQUEST, "HttpRequest");
+
+    let _ = (U16, HttpRequest, ReadByte, WriteByte);
+    assert_eq!(camel_read_byte(), "readByte");
+    assert_eq!(camel_write_byte(), "writeByte");
+}
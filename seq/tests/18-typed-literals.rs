@@ -0,0 +1,52 @@
+// The loop variable normally becomes an unsuffixed literal like `3`, whose
+// type is left to inference. That is not always enough: a method call on the
+// literal does not compile when the type is ambiguous, and const generic
+// arguments and array lengths need a specific type.
+//
+// Let the header give the loop variable an integer type, so that every
+// occurrence turns into a suffixed literal like `3u8`:
+//
+//     seq!(N: u8 in 0..16 { ... })
+//
+// A single occurrence can also be given a type of its own with `#N:usize`.
+// This works for #FIRST, #LAST and #COUNT too.
+//
+// A value that does not fit in the type is a compile error.
+
+use seq::seq;
+
+struct Wrapper<const N: u8>;
+
+impl<const N: u8> Wrapper<N> {
+    fn get(&self) -> u8 {
+        N
+    }
+}
+
+seq!(N: u8 in 250..=255 {
+    fn wrapping() -> [u8; 6] {
+        [#(N.wrapping_add(1),)*]
+    }
+
+    fn wrapper() -> u8 {
+        Wrapper::<#LAST>.get()
+    }
+});
+
+seq!(N in 0..4 {
+    fn buffers#N() -> ([u8; #N:usize], [u8; #COUNT:usize], u32, i64) {
+        ([0; #N:usize], [0; #COUNT:usize], #N:u32.count_ones(), -#LAST:i64)
+    }
+});
+
+seq!(N: i8 in -3..0 {
+    const NEGATIVE: [i8; 3] = [#(N.abs(),)*];
+});
+
+fn main() {
+    assert_eq!(wrapping(), [251, 252, 253, 254, 255, 0]);
+    assert_eq!(wrapper(), 255);
+    assert_eq!(buffers0(), ([], [0; 4], 0, -3));
+    assert_eq!(buffers3(), ([0; 3], [0; 4], 2, -3));
+    assert_eq!(NEGATIVE, [3, 2, 1]);
+}
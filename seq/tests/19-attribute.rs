@@ -0,0 +1,114 @@
+// The function-like seq! wraps the whole item in braces, which keeps rustfmt
+// and IDEs from looking inside. Provide an attribute form that goes on the
+// item itself and repeats it:
+//
+//     #[seq::repeat(N in 0..4)]
+//     fn square__N__() -> u32 { N * N }
+//
+// Since the item has to be valid Rust before the attribute gets to see it,
+// `square#N` cannot be written there. Instead, a loop variable is pasted into
+// an identifier by writing it between double underscores. Inside strings,
+// "#N" works as usual.
+//
+// Marking elements inside the item with #[seq::each] makes the attribute emit
+// the item once and repeat just those elements. This works for enum variants,
+// struct fields and match arms, without the enum-wide workaround of test 05.
+// An element can contain commas outside of any brackets, as in
+// `HashMap<u8, u16>`.
+
+use seq::seq;
+use std::collections::HashMap;
+
+#[seq::repeat(N in 1..4)]
+fn square__N__() -> u32 {
+    N * N
+}
+
+#[seq::repeat(N in 0..4)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Irq {
+    #[seq::each]
+    Irq__N__ = N,
+    Spurious = 255,
+}
+
+#[seq::repeat(N in 0..4)]
+impl Irq {
+    fn name(self) -> &'static str {
+        match self {
+            #[seq::each]
+            Irq::Irq__N__ => "irq#N",
+            Irq::Spurious => "spurious",
+        }
+    }
+
+    fn is_odd(self) -> bool {
+        match self {
+            #[seq::each]
+            Irq::Irq__N__ => {
+                N % 2 == 1
+            }
+            Irq::Spurious => false,
+        }
+    }
+
+    fn width(self) -> usize {
+        match self {
+            #[seq::each]
+            Irq::Irq__N__ => width::<u8, [u8; N]>(),
+            Irq::Spurious => 0,
+        }
+    }
+}
+
+fn width<A, B>() -> usize {
+    std::mem::size_of::<A>() + std::mem::size_of::<B>()
+}
+
+#[seq::repeat(N in 0..3)]
+#[derive(Default)]
+struct Regs {
+    #[seq::each]
+    reg__N__: u32,
+    #[seq::each]
+    map__N__: HashMap<u8, u16>,
+}
+
+// Several inherent impls of the same type are fine.
+#[seq::repeat(N in 0..3)]
+impl Regs {
+    fn set__N__(&mut self, value: u32) {
+        self.reg__N__ = value;
+    }
+}
+
+#[seq::repeat(T in [u8, u16])]
+fn max__T__() -> T {
+    T::MAX
+}
+
+// The attribute and seq! produce the same.
+seq!(N in 0..4 {
+    const IRQS: [Irq; 4] = [#(Irq::Irq#N,)*];
+});
+
+fn main() {
+    assert_eq!(square1(), 1);
+    assert_eq!(square3(), 9);
+
+    assert_eq!(Irq::Irq2 as u8, 2);
+    assert_eq!(IRQS[3], Irq::Irq3);
+    assert_eq!(Irq::Irq3.name(), "irq3");
+    assert_eq!(Irq::Spurious.name(), "spurious");
+    assert!(Irq::Irq1.is_odd());
+    assert!(!Irq::Irq2.is_odd());
+    assert_eq!(Irq::Irq3.width(), 4);
+
+    let mut regs = Regs::default();
+    regs.set2(7);
+    assert_eq!(regs.reg2, 7);
+    regs.map1.insert(1, 2);
+    assert_eq!(regs.map1[&1] + regs.map2.len() as u16, 2);
+
+    assert_eq!(maxu16(), 65535);
+}
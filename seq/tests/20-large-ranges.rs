@@ -0,0 +1,34 @@
+// Lookup tables can be large. Since every iteration adds to the expansion,
+// the total number of iterations is capped at 65536 by default, counting each
+// iteration of a nested loop. A header can raise the cap:
+//
+//     seq!(N in 0..65536, limit = 131072 { ... })
+//
+// Going over the limit is an error at the range, or for nested loops at the
+// loop variable that crosses it, rather than an expansion that takes minutes
+// to compile or runs out of memory.
+//
+// The body is split into tokens once, and expressions are parsed once, so
+// that every iteration only does the substitution itself. This test only
+// checks that tables this large work; the time it takes to expand them is
+// checked by an ignored unit test in src/expand.rs, which runs with
+// `cargo test -- --ignored`.
+
+use seq::seq;
+
+seq!(N in 0..65536 {
+    static SQUARES: [u32; 65536] = [#(N * N,)*];
+});
+
+seq!(N in 0..65536, limit = 196_608 {
+    static BYTES: [u8; 65536] = [#(#(N % 256),)*];
+    static NAMES: [&str; 65536] = [#("n#N",)*];
+    static ZEROS: [u8; 65536] = [#(0,)*];
+});
+
+fn main() {
+    assert_eq!(SQUARES[65535], 4294836225);
+    assert_eq!(BYTES[300], 44);
+    assert_eq!(NAMES[65535], "n65535");
+    assert_eq!(ZEROS.iter().map(|&zero| zero as usize).sum::<usize>(), 0);
+}
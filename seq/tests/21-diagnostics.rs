@@ -0,0 +1,35 @@
+// Mistakes in the header are reported with a specific message at the part of
+// the header that is wrong. In particular a constant cannot be the bound of a
+// range, because macros expand before constants get a value; see test 09 for
+// how to share a bound between seq! and other code instead.
+//
+// In the body, each #(...)* section iterates one variable, so a variable that
+// no enclosing section iterates has no value there.
+
+use seq::seq;
+
+const LEN: usize = 4;
+
+seq!(N in 0..LEN {});
+
+seq!(in 0..4 {});
+
+seq!(N 0..4 {});
+
+seq!(N in 0..4);
+
+seq!(N in 0..4 => {});
+
+macro_rules! expr_bound {
+    ($len:expr) => {
+        seq!(N in 0..$len {});
+    };
+}
+
+expr_bound!(LEN);
+
+const NAMES: [&str; 2] = seq!(N in 0..2, M in 0..2 {
+    [#(stringify!(reg_#N#_#M),)*]
+});
+
+fn main() {}
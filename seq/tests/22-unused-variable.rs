@@ -0,0 +1,62 @@
+// A loop variable that is never used produces the same tokens for every
+// value. That is more likely a mistake than intended, so it is a warning, and
+// so is a #(...)* section that never mentions its variable while the rest of
+// the body does. Naming the variable with a leading underscore, like `_N`,
+// says that it is intended, as in `[#(0,)*]` for an array of zeros.
+//
+// Proc macros cannot emit warnings on stable Rust, so this one is a
+// deprecation warning on a function called at the right span. The call goes
+// into the first function body of the expansion, which works wherever seq! is
+// invoked, including inside an impl. Without a function body, it goes before
+// statements like `println!("hi");` and into a block with a lone expression.
+// An expansion of only items and no function body, like the statics below,
+// gets no warning. It is denied here so that the test fails to compile.
+
+#![deny(deprecated)]
+
+use seq::seq;
+
+seq!(N in 0..1 {
+    fn one() -> u8 {
+        1
+    }
+});
+
+pub struct Flags;
+
+impl Flags {
+    seq!(N in 0..4, M in 0..2 {
+        #(
+            pub const BIT#N: u8 = 1 << N;
+        )*
+
+        pub fn bits(&self) -> u8 {
+            0
+        }
+    });
+}
+
+seq!(N in 0..2 {
+    static ZEROS: [u8; 2] = [#(0,)*];
+});
+
+seq!(N in 0..2 {
+    fn zeros_and_values() -> ([u8; 2], [u8; 2]) {
+        ([#(0,)*], [#(N,)*])
+    }
+});
+
+seq!(_N in 0..2 {
+    fn more_zeros_and_values() -> ([u8; 2], [u8; 2]) {
+        ([#(0,)*], [#(_N,)*])
+    }
+});
+
+fn main() {
+    seq!(N in 0..3 {
+        println!("hi");
+    });
+
+    let five = seq!(N in 0..1 { 5 });
+    assert_eq!(five, 5);
+}
@@ -0,0 +1,89 @@
+// Mapping a runtime integer onto generated items is common enough to get its
+// own macros. seq::dispatch! expands to a match with one arm per value:
+//
+//     seq::dispatch!(n, N in 0..4 => f#N(), _ => fallback())
+//
+// is
+//
+//     match n {
+//         0 => f0(),
+//         1 => f1(),
+//         2 => f2(),
+//         3 => f3(),
+//         _ => fallback(),
+//     }
+//
+// Without a fallback arm, a value outside of the range panics. With
+// `(I, T) in enumerate [...]` the arms match the index I. The value and the
+// arms can be any expression, including ones with commas in generic
+// arguments like `f::<A, B>()`.
+//
+// seq::table! produces a static or const array with one element per value,
+// so that the array can be indexed by N:
+//
+//     seq::table!(static HANDLERS: [fn() -> u32] = N in 0..4 => f#N);
+//
+// The length of the array is filled in from the range.
+
+fn f0() -> u32 {
+    0
+}
+
+fn f1() -> u32 {
+    10
+}
+
+fn f2() -> u32 {
+    20
+}
+
+fn f3() -> u32 {
+    30
+}
+
+fn call(n: usize) -> u32 {
+    seq::dispatch!(n, N in 0..4 => f#N(), _ => u32::MAX)
+}
+
+fn call_or_panic(n: u8) -> u32 {
+    seq::dispatch!(n, N: u8 in 0..4 => f#N())
+}
+
+fn width<A, B>() -> usize {
+    std::mem::size_of::<A>() + std::mem::size_of::<B>()
+}
+
+fn widths(n: u64) -> usize {
+    seq::dispatch!(
+        std::convert::identity::<u64>(n) as usize,
+        N in 0..4 => width::<u8, [u8; N]>(),
+        _ => width::<u64, u64>(),
+    )
+}
+
+fn name(n: usize) -> &'static str {
+    seq::dispatch!(n, (I, T) in enumerate [u8, u16, u32] => stringify!(T), _ => "?")
+}
+
+seq::table!(static HANDLERS: [fn() -> u32] = N in 0..4 => f#N);
+
+seq::table!(pub const SQUARES: [u64] = N in 0..=8 => N * N);
+
+fn main() {
+    assert_eq!(call(0), 0);
+    assert_eq!(call(3), 30);
+    assert_eq!(call(4), u32::MAX);
+
+    assert_eq!(call_or_panic(2), 20);
+
+    assert_eq!(widths(0), 1);
+    assert_eq!(widths(3), 4);
+    assert_eq!(widths(4), 16);
+
+    assert_eq!(name(1), "u16");
+    assert_eq!(name(3), "?");
+
+    assert_eq!(HANDLERS.len(), 4);
+    assert_eq!(HANDLERS[1](), 10);
+    assert_eq!(SQUARES, [0, 1, 4, 9, 16, 25, 36, 49, 64]);
+}
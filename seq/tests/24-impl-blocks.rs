@@ -0,0 +1,56 @@
+// Like any macro in item position, seq! can be invoked inside an impl or a
+// trait to generate associated items. Nothing else may appear there, so the
+// expansion must consist of exactly the items of the body, even when it gets
+// a warning about an unused loop variable.
+
+use seq::seq;
+
+pub struct Registers([u32; 4]);
+
+impl Registers {
+    seq!(N in 0..4 {
+        pub fn reg#N(&self) -> u32 {
+            self.0[N]
+        }
+    });
+
+    seq!(N in 0..1 {
+        pub fn first(&self) -> u32 {
+            self.0[0]
+        }
+    });
+}
+
+pub trait Lanes {
+    seq!(N in 0..2 {
+        fn lane#N(&self) -> u8;
+    });
+
+    seq!(N in 0..1 {
+        fn lanes(&self) -> u8 {
+            2
+        }
+    });
+}
+
+impl Lanes for Registers {
+    seq!(N in 0..2 {
+        fn lane#N(&self) -> u8 {
+            self.0[N] as u8
+        }
+    });
+
+    seq!(N in 0..1 {
+        fn lanes(&self) -> u8 {
+            4
+        }
+    });
+}
+
+fn main() {
+    let registers = Registers([1, 2, 3, 4]);
+    assert_eq!(registers.reg3(), 4);
+    assert_eq!(registers.first(), 1);
+    assert_eq!(registers.lane1(), 2);
+    assert_eq!(registers.lanes(), 4);
+}
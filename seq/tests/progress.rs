@@ -1,13 +1,28 @@
 #[test]
 fn tests() {
     let t = trybuild::TestCases::new();
-    //t.pass("tests/01-parse-header.rs");
-    //t.pass("tests/02-parse-body.rs");
-    //t.compile_fail("tests/03-expand-four-errors.rs");
-    //t.pass("tests/04-paste-ident.rs");
-    //t.pass("tests/05-repeat-section.rs");
-    //t.pass("tests/06-init-array.rs");
-    //t.pass("tests/07-inclusive-range.rs");
-    //t.compile_fail("tests/08-ident-span.rs");
-    //t.pass("tests/09-interaction-with-macrorules.rs");
+    t.pass("tests/01-parse-header.rs");
+    t.pass("tests/02-parse-body.rs");
+    t.compile_fail("tests/03-expand-four-errors.rs");
+    t.pass("tests/04-paste-ident.rs");
+    t.pass("tests/05-repeat-section.rs");
+    t.pass("tests/06-init-array.rs");
+    t.pass("tests/07-inclusive-range.rs");
+    t.compile_fail("tests/08-ident-span.rs");
+    t.pass("tests/09-interaction-with-macrorules.rs");
+    t.pass("tests/10-multiple-vars.rs");
+    t.pass("tests/11-ranges.rs");
+    t.compile_fail("tests/12-invalid-ranges.rs");
+    t.pass("tests/13-lists.rs");
+    t.pass("tests/14-expressions.rs");
+    t.pass("tests/15-separators.rs");
+    t.pass("tests/16-conditionals.rs");
+    t.pass("tests/17-case-and-strings.rs");
+    t.pass("tests/18-typed-literals.rs");
+    t.pass("tests/19-attribute.rs");
+    t.pass("tests/20-large-ranges.rs");
+    t.compile_fail("tests/21-diagnostics.rs");
+    t.compile_fail("tests/22-unused-variable.rs");
+    t.pass("tests/23-dispatch-and-tables.rs");
+    t.pass("tests/24-impl-blocks.rs");
 }
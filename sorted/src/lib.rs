@@ -1,11 +1,52 @@
 extern crate proc_macro;
 
+mod matches;
+mod order;
+
+use crate::order::Order;
 use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::ToTokens;
+use syn::parse::Nothing;
+use syn::{parse_macro_input, Error, Item, ItemFn, Result};
 
 #[proc_macro_attribute]
 pub fn sorted(args: TokenStream, input: TokenStream) -> TokenStream {
-    let _ = args;
-    let _ = input;
+    let mut order = Order::default();
+    let parser = syn::meta::parser(|meta| order.parse(meta));
+    parse_macro_input!(args with parser);
+    let item = parse_macro_input!(input as Item);
+    let mut output = item.to_token_stream();
+    if let Err(error) = variants(order, &item) {
+        output.extend(error.into_compile_error());
+    }
+    output.into()
+}
+
+#[proc_macro_attribute]
+pub fn check(args: TokenStream, input: TokenStream) -> TokenStream {
+    parse_macro_input!(args as Nothing);
+    let mut item = parse_macro_input!(input as ItemFn);
+    let result = matches::check(&mut item);
+    let mut output = item.to_token_stream();
+    if let Err(error) = result {
+        output.extend(error.into_compile_error());
+    }
+    output.into()
+}
 
-    unimplemented!()
+fn variants(order: Order, item: &Item) -> Result<()> {
+    let item = match item {
+        Item::Enum(item) => item,
+        _ => {
+            let msg = "expected enum or match expression";
+            return Err(Error::new(Span::call_site(), msg));
+        }
+    };
+    let names = item
+        .variants
+        .iter()
+        .map(|variant| (variant.ident.to_string(), variant.ident.to_token_stream()))
+        .collect::<Vec<_>>();
+    order.check(&names)
 }
@@ -0,0 +1,81 @@
+use crate::order::Order;
+use quote::ToTokens;
+use syn::visit_mut::{self, VisitMut};
+use syn::{Attribute, Error, ExprMatch, ItemFn, Meta, Pat, Path, Result};
+
+// Checks every match expression in the function that carries #[sorted],
+// taking the attribute off, since stable Rust does not allow attribute
+// macros on expressions.
+pub fn check(item: &mut ItemFn) -> Result<()> {
+    let mut visitor = Visitor { error: None };
+    visitor.visit_item_fn_mut(item);
+    match visitor.error {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+struct Visitor {
+    error: Option<Error>,
+}
+
+impl VisitMut for Visitor {
+    fn visit_expr_match_mut(&mut self, node: &mut ExprMatch) {
+        let sorted = node
+            .attrs
+            .iter()
+            .position(|attr| attr.path().is_ident("sorted"));
+        if let Some(i) = sorted {
+            let attr = node.attrs.remove(i);
+            if let Err(error) = arms(&attr, node) {
+                match &mut self.error {
+                    Some(errors) => errors.combine(error),
+                    None => self.error = Some(error),
+                }
+            }
+        }
+        visit_mut::visit_expr_match_mut(self, node);
+    }
+}
+
+// The arms compare by the path of their pattern, like `Error::Fmt` for
+// `Error::Fmt(e)`. A wildcard arm is allowed only at the end.
+fn arms(attr: &Attribute, node: &ExprMatch) -> Result<()> {
+    let mut order = Order::default();
+    if !matches!(attr.meta, Meta::Path(_)) {
+        attr.parse_nested_meta(|meta| order.parse(meta))?;
+    }
+    let mut names = Vec::new();
+    for (i, arm) in node.arms.iter().enumerate() {
+        let path = match &arm.pat {
+            Pat::Path(pat) => &pat.path,
+            Pat::TupleStruct(pat) => &pat.path,
+            Pat::Struct(pat) => &pat.path,
+            Pat::Ident(pat) if pat.subpat.is_none() => {
+                names.push((pat.ident.to_string(), pat.ident.to_token_stream()));
+                continue;
+            }
+            Pat::Wild(_) if i + 1 == node.arms.len() => continue,
+            Pat::Wild(pat) => {
+                let msg = "the wildcard pattern should be the last arm";
+                return Err(Error::new_spanned(pat, msg));
+            }
+            pat => return Err(Error::new_spanned(pat, "unsupported by #[sorted]")),
+        };
+        names.push((path_to_string(path), path.to_token_stream()));
+    }
+    order.check(&names)
+}
+
+fn path_to_string(path: &Path) -> String {
+    let segments = path
+        .segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::");
+    match path.leading_colon {
+        Some(_) => format!("::{}", segments),
+        None => segments,
+    }
+}
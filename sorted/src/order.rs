@@ -0,0 +1,115 @@
+use proc_macro2::TokenStream;
+use std::cmp::Ordering;
+use syn::meta::ParseNestedMeta;
+use syn::{Error, Result};
+
+// How #[sorted] compares two names. Without arguments that is plain string
+// comparison, in which `Http10` sorts before `Http2` and `Zip` before `gzip`.
+#[derive(Copy, Clone, Default)]
+pub struct Order {
+    // Runs of digits compare by their value, so `Http2` sorts before
+    // `Http10`, from #[sorted(natural)].
+    natural: bool,
+    // Letters compare without regard to case, from
+    // #[sorted(case_insensitive)].
+    case_insensitive: bool,
+    // Descending instead of ascending, from #[sorted(reverse)].
+    reverse: bool,
+}
+
+impl Order {
+    // One argument of #[sorted(...)]. The arguments combine, as in
+    // #[sorted(natural, case_insensitive)].
+    pub fn parse(&mut self, meta: ParseNestedMeta) -> Result<()> {
+        let (name, flag) = if meta.path.is_ident("natural") {
+            ("natural", &mut self.natural)
+        } else if meta.path.is_ident("case_insensitive") {
+            ("case_insensitive", &mut self.case_insensitive)
+        } else if meta.path.is_ident("reverse") {
+            ("reverse", &mut self.reverse)
+        } else {
+            let msg =
+                "unsupported sorted attribute, expected `natural`, `case_insensitive` or `reverse`";
+            return Err(meta.error(msg));
+        };
+        if *flag {
+            return Err(meta.error(format!("duplicate sorted attribute `{}`", name)));
+        }
+        *flag = true;
+        Ok(())
+    }
+
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        let (a, b) = if self.case_insensitive {
+            (a.to_lowercase(), b.to_lowercase())
+        } else {
+            (a.to_owned(), b.to_owned())
+        };
+        let ordering = if self.natural {
+            natural(&a, &b)
+        } else {
+            a.cmp(&b)
+        };
+        if self.reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
+    // Fails at the first name that should sort before one of the names ahead
+    // of it, pointing out the earliest such name.
+    pub fn check(&self, names: &[(String, TokenStream)]) -> Result<()> {
+        for (i, (name, tokens)) in names.iter().enumerate() {
+            let earlier = names[..i]
+                .iter()
+                .find(|(earlier, _)| self.compare(name, earlier) == Ordering::Less);
+            if let Some((earlier, _)) = earlier {
+                let msg = format!("{} should sort before {}", name, earlier);
+                return Err(Error::new_spanned(tokens, msg));
+            }
+        }
+        Ok(())
+    }
+}
+
+// Compares the names run by run, where a run is either all digits or has no
+// digits at all. Two runs of digits compare by value, and only if that is
+// equal, as in `V01` and `V1`, by the digits as written.
+fn natural(a: &str, b: &str) -> Ordering {
+    let mut a = runs(a);
+    let mut b = runs(b);
+    loop {
+        let (a, b) = match (a.next(), b.next()) {
+            (Some(a), Some(b)) => (a, b),
+            (a, b) => return a.is_some().cmp(&b.is_some()),
+        };
+        let is_number = |run: &str| run.starts_with(|ch: char| ch.is_ascii_digit());
+        let ordering = if is_number(a) && is_number(b) {
+            let a_value = a.trim_start_matches('0');
+            let b_value = b.trim_start_matches('0');
+            a_value
+                .len()
+                .cmp(&b_value.len())
+                .then_with(|| a_value.cmp(b_value))
+                .then_with(|| a.cmp(b))
+        } else {
+            a.cmp(b)
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+fn runs(mut rest: &str) -> impl Iterator<Item = &str> {
+    std::iter::from_fn(move || {
+        let digit = rest.chars().next()?.is_ascii_digit();
+        let len = rest
+            .find(|ch: char| ch.is_ascii_digit() != digit)
+            .unwrap_or(rest.len());
+        let (run, tail) = rest.split_at(len);
+        rest = tail;
+        Some(run)
+    })
+}
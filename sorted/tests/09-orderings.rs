@@ -0,0 +1,75 @@
+// Plain string comparison puts `Http10` and `Http11` before `Http2`, and every
+// uppercase letter before every lowercase one. The arguments of #[sorted]
+// choose a different order:
+//
+//   - #[sorted(natural)] compares runs of digits by their value, so that
+//     `Http2` sorts before `Http10`.
+//
+//   - #[sorted(case_insensitive)] compares letters without regard to case, so
+//     that `Gzip` and `XML` are in order around `json`.
+//
+//   - #[sorted(reverse)] expects descending order.
+//
+// The arguments can be combined, and a #[sorted(...)] on a match expression
+// inside #[sorted::check] takes the same arguments as on an enum.
+
+use sorted::sorted;
+
+#[sorted(natural)]
+pub enum Protocol {
+    Http1,
+    Http2,
+    Http10,
+    Http11,
+}
+
+#[sorted(case_insensitive)]
+#[allow(non_camel_case_types)]
+pub enum Encoding {
+    Gzip,
+    json,
+    XML,
+}
+
+#[sorted(reverse)]
+pub enum Priority {
+    Urgent,
+    Normal,
+    Low,
+}
+
+#[sorted(natural, case_insensitive, reverse)]
+#[allow(non_camel_case_types)]
+pub enum Version {
+    v10,
+    V9,
+    v2,
+}
+
+#[sorted::check]
+fn port(protocol: &Protocol) -> u16 {
+    #[sorted(natural)]
+    match protocol {
+        Protocol::Http1 => 80,
+        Protocol::Http2 => 443,
+        Protocol::Http10 => 8080,
+        _ => 0,
+    }
+}
+
+#[sorted::check]
+fn mime(encoding: &Encoding) -> &'static str {
+    use self::Encoding::*;
+
+    #[sorted(case_insensitive)]
+    match encoding {
+        Gzip => "application/gzip",
+        json => "application/json",
+        XML => "application/xml",
+    }
+}
+
+fn main() {
+    assert_eq!(port(&Protocol::Http10), 8080);
+    assert_eq!(mime(&Encoding::json), "application/json");
+}
@@ -0,0 +1,48 @@
+// An enum that is out of order under the chosen order gets the same error as
+// in test 03, naming the variant that it should sort before. An argument
+// other than `natural`, `case_insensitive` or `reverse` is an error, and so is
+// giving the same one twice.
+
+use sorted::sorted;
+
+#[sorted(natural)]
+pub enum Protocol {
+    Http1,
+    Http10,
+    Http2,
+}
+
+#[sorted(reverse)]
+pub enum Priority {
+    Low,
+    Normal,
+    Urgent,
+}
+
+#[sorted(alphabetical)]
+pub enum Alphabet {
+    A,
+    B,
+}
+
+#[sorted(natural, natural)]
+pub enum Digit {
+    D1,
+    D2,
+}
+
+pub enum Encoding {
+    Gzip,
+    Json,
+}
+
+#[sorted::check]
+fn f(encoding: Encoding) -> u8 {
+    #[sorted(reverse)]
+    match encoding {
+        Encoding::Gzip => 1,
+        Encoding::Json => 2,
+    }
+}
+
+fn main() {}